@@ -0,0 +1,239 @@
+//! Defines a wrapper around asynchronous readers and writers to display a
+//! progress bar, requires the `tokio` or the `futures-io` feature.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use pin_project_lite::pin_project;
+
+use crate::step_progress::{StepProgress, WithStepProgress};
+
+pin_project! {
+    /// A wrapper arround an asynchronous reader or writer which counts
+    /// transferred bytes.
+    ///
+    /// The total size is unknown by default, it can be specified using
+    /// `with_max_step`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "futures-io")]
+    /// # {
+    /// use futures::executor::block_on;
+    /// use futures::io::{AsyncReadExt, Cursor};
+    /// use prog_rs::prelude::*;
+    /// use prog_rs::AsyncProgress;
+    ///
+    /// let data = vec![0; 1_000_000];
+    /// let mut reader = AsyncProgress::new(Cursor::new(data))
+    ///     .with_max_step(1_000_000)
+    ///     .with_prefix("Downloading ...");
+    ///
+    /// let mut buf = Vec::new();
+    /// block_on(reader.read_to_end(&mut buf)).unwrap();
+    /// # }
+    /// ```
+    #[derive(Debug)]
+    pub struct AsyncProgress<T> {
+        #[pin]
+        inner: T,
+        step_progress: StepProgress,
+    }
+}
+
+impl<T> AsyncProgress<T> {
+    /// Wrap an asynchronous reader or writer.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            step_progress: StepProgress::new().with_humanize(true).with_unit("B"),
+        }
+    }
+
+    /// Get a reference to the wrapped reader or writer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwrap the reader or writer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Count the result of a read, an empty read means the end of the input.
+    fn after_read(step_progress: &mut StepProgress, count: usize) {
+        if count == 0 {
+            step_progress.finish();
+        } else {
+            step_progress.step(count);
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> tokio::io::AsyncRead for AsyncProgress<T>
+where
+    T: tokio::io::AsyncRead,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.project();
+        let capacity = buf.remaining();
+        let before = buf.filled().len();
+        let res = this.inner.poll_read(cx, buf);
+
+        if let Poll::Ready(Ok(())) = res {
+            if capacity > 0 {
+                Self::after_read(this.step_progress, buf.filled().len() - before);
+            }
+        }
+
+        res
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> tokio::io::AsyncWrite for AsyncProgress<T>
+where
+    T: tokio::io::AsyncWrite,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let res = this.inner.poll_write(cx, buf);
+
+        if let Poll::Ready(Ok(count)) = res {
+            this.step_progress.step(count);
+        }
+
+        res
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.project();
+        let res = this.inner.poll_shutdown(cx);
+
+        if let Poll::Ready(Ok(())) = res {
+            this.step_progress.finish();
+        }
+
+        res
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> futures_io::AsyncRead for AsyncProgress<T>
+where
+    T: futures_io::AsyncRead,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let res = this.inner.poll_read(cx, buf);
+
+        if let Poll::Ready(Ok(count)) = res {
+            if !buf.is_empty() {
+                Self::after_read(this.step_progress, count);
+            }
+        }
+
+        res
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<T> futures_io::AsyncWrite for AsyncProgress<T>
+where
+    T: futures_io::AsyncWrite,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let res = this.inner.poll_write(cx, buf);
+
+        if let Poll::Ready(Ok(count)) = res {
+            this.step_progress.step(count);
+        }
+
+        res
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.project();
+        let res = this.inner.poll_close(cx);
+
+        if let Poll::Ready(Ok(())) = res {
+            this.step_progress.finish();
+        }
+
+        res
+    }
+}
+
+impl<T> WithStepProgress for AsyncProgress<T> {
+    fn get_step_progress(&mut self) -> &mut StepProgress {
+        &mut self.step_progress
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use futures::executor::block_on;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+    use crate::progress::WithProgress;
+    use crate::utils::sink_output;
+
+    #[test]
+    fn tokio_read() {
+        let data = vec![1; 10_000];
+        let mut reader = AsyncProgress::new(data.as_slice())
+            .with_max_step(data.len())
+            .with_output_stream(sink_output());
+
+        let mut buf = Vec::new();
+        block_on(reader.read_to_end(&mut buf)).unwrap();
+
+        assert_eq!(buf, data);
+        assert_eq!(reader.cur_step(), data.len());
+        assert!(reader.step_progress.render(80).contains("100.0%"));
+    }
+
+    #[test]
+    fn tokio_write() {
+        let data = vec![1; 10_000];
+        let mut writer = AsyncProgress::new(Vec::new()).with_output_stream(sink_output());
+
+        block_on(async {
+            writer.write_all(&data).await.unwrap();
+            writer.shutdown().await.unwrap();
+        });
+
+        assert_eq!(writer.cur_step(), data.len());
+        assert!(writer.step_progress.render(80).contains("100.0%"));
+        assert_eq!(writer.into_inner(), data);
+    }
+}
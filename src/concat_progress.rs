@@ -0,0 +1,158 @@
+//! Defines a reader over the concatenation of several files, which displays
+//! a single progress bar.
+
+use crate::progress::WithProgress;
+use crate::step_progress::{StepProgress, WithStepProgress};
+
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::fs::{self, File};
+use std::io;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// A wrapper read only stream over the concatenation of several files, its
+/// total size is the sum of the sizes of the files.
+///
+/// Files are opened one at a time, the name of the file being read is
+/// displayed as a prefix.
+///
+/// # Example
+///
+/// ```no_run
+/// use prog_rs::ConcatFileProgress;
+/// # use std::io::{BufRead, BufReader};
+///
+/// let paths = (0..200).map(|i| format!("shards/part-{:03}.csv", i));
+/// let f = BufReader::new(ConcatFileProgress::open(paths).unwrap());
+/// println!("These files have {} lines", f.lines().count());
+/// ```
+#[derive(Debug)]
+pub struct ConcatFileProgress {
+    current: Option<File>,
+    paths: VecDeque<PathBuf>,
+    step_progress: StepProgress,
+}
+
+impl ConcatFileProgress {
+    /// Prepare to read given files in order, their sizes are read
+    /// immediately.
+    pub fn open<I, P>(paths: I) -> io::Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let paths: VecDeque<_> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect();
+
+        let mut max_step: u64 = 0;
+
+        for path in &paths {
+            max_step += fs::metadata(path)?.len();
+        }
+
+        Ok(Self {
+            current: None,
+            paths,
+            step_progress: StepProgress::new()
+                .with_humanize(true)
+                .with_unit("B")
+                .with_max_step(max_step.try_into().expect("file size doesn't fit in usize")),
+        })
+    }
+
+    /// Open the next file, return `false` if all files were read.
+    fn open_next(&mut self) -> io::Result<bool> {
+        let path = match self.paths.pop_front() {
+            Some(path) => path,
+            None => return Ok(false),
+        };
+
+        self.current = Some(File::open(&path)?);
+
+        if let Some(name) = path.file_name() {
+            self.get_progress().set_prefix(name.to_string_lossy());
+        }
+
+        Ok(true)
+    }
+}
+
+impl Read for ConcatFileProgress {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            if self.current.is_none() && !self.open_next()? {
+                self.step_progress.finish();
+                return Ok(0);
+            }
+
+            let step = self.current.as_mut().unwrap().read(buf)?;
+
+            if step > 0 {
+                self.step_progress.step(step);
+                return Ok(step);
+            }
+
+            self.current = None;
+        }
+    }
+}
+
+impl WithStepProgress for ConcatFileProgress {
+    fn get_step_progress(&mut self) -> &mut StepProgress {
+        &mut self.step_progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::sink_output;
+
+    #[test]
+    fn read_all_files() {
+        let dir = std::env::temp_dir().join(format!("prog_rs-concat-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let paths = ["first", "empty", "last"].map(|name| dir.join(name));
+        fs::write(&paths[0], [0; 10]).unwrap();
+        fs::write(&paths[1], []).unwrap();
+        fs::write(&paths[2], [1; 20]).unwrap();
+
+        // The estimated time is only displayed until the end
+        let mut reader = ConcatFileProgress::open(&paths)
+            .unwrap()
+            .with_humanize(false)
+            .with_unit("")
+            .with_template("{prefix} {pos}/{total} {eta}".parse().unwrap())
+            .with_output_stream(sink_output());
+
+        assert_eq!(reader.max_step(), Some(30));
+        let mut buf = [0; 8];
+
+        // Reads don't overlap two files
+        assert_eq!(reader.read(&mut buf).unwrap(), 8);
+        assert!(reader.step_progress.render(80).starts_with("first 8/30 "));
+        assert_eq!(reader.read(&mut buf).unwrap(), 2);
+        assert!(reader.step_progress.render(80).starts_with("first 10/30 "));
+
+        // The empty file is skipped
+        assert_eq!(reader.read(&mut buf).unwrap(), 8);
+        assert_eq!(buf, [1; 8]);
+        assert!(reader.step_progress.render(80).starts_with("last 18/30 "));
+        assert!(!reader.step_progress.render(80).ends_with(' '));
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, [1; 12]);
+        assert_eq!(reader.step_progress.render(80), "last 30/30 ");
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
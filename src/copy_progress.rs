@@ -0,0 +1,207 @@
+//! Defines helpers copying data between a reader and a writer while
+//! displaying a progress bar.
+
+use crate::step_progress::{StepProgress, WithStepProgress};
+
+use std::convert::TryInto;
+use std::fs::File;
+use std::io;
+use std::io::prelude::*;
+
+/// Number of bytes copied between two updates of the progress bar.
+///
+/// Each chunk is copied using `io::copy`, which can rely on kernel
+/// acceleration (such as `copy_file_range` or `sendfile` on Linux) for some
+/// kinds of readers and writers: the chunks must be large enough for the
+/// system calls not to become a bottleneck.
+const CHUNK_SIZE: u64 = 4 << 20;
+
+/// Copy the entire content of a reader into a writer while displaying a
+/// progress bar, return the number of bytes copied.
+///
+/// # Example
+///
+/// ```
+/// # use std::io::Cursor;
+/// let mut reader = Cursor::new(vec![0; 10_000_000]);
+/// let mut writer = Vec::new();
+/// prog_rs::copy(&mut reader, &mut writer).unwrap();
+/// ```
+pub fn copy<R, W>(reader: &mut R, writer: &mut W) -> io::Result<u64>
+where
+    R: Read + ?Sized,
+    W: Write + ?Sized,
+{
+    let step_progress = StepProgress::new().with_humanize(true).with_unit("B");
+    copy_with_progress(reader, writer, step_progress)
+}
+
+/// Copy the remaining content of a file into another file while displaying
+/// a progress bar, the total size is read from the file's metadata.
+pub fn copy_file(reader: &mut File, writer: &mut File) -> io::Result<u64> {
+    let step_progress = StepProgress::new().with_humanize(true).with_unit("B");
+    copy_file_with_progress(reader, writer, step_progress)
+}
+
+/// Copy the remaining content of a file into another file, using given
+/// progress bar with the remaining size as total.
+fn copy_file_with_progress(
+    reader: &mut File,
+    writer: &mut File,
+    step_progress: StepProgress,
+) -> io::Result<u64> {
+    let remaining = remaining_len(reader)?;
+
+    let step_progress = step_progress.with_max_step(
+        remaining
+            .try_into()
+            .expect("file size doesn't fit in usize"),
+    );
+
+    copy_with_progress(reader, writer, step_progress)
+}
+
+/// Get the number of bytes left to read in a file.
+fn remaining_len(file: &mut File) -> io::Result<u64> {
+    Ok(file
+        .metadata()?
+        .len()
+        .saturating_sub(file.stream_position()?))
+}
+
+/// Copy the entire content of a reader into a writer, using given progress
+/// bar, return the number of bytes copied.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::prelude::*;
+/// use prog_rs::StepProgress;
+/// # use std::io::Cursor;
+///
+/// let mut reader = Cursor::new(vec![0; 10_000_000]);
+/// let mut writer = Vec::new();
+///
+/// prog_rs::copy_with_progress(
+///     &mut reader,
+///     &mut writer,
+///     StepProgress::new()
+///         .with_humanize(true)
+///         .with_unit("B")
+///         .with_max_step(10_000_000)
+///         .with_prefix("Copying ..."),
+/// )
+/// .unwrap();
+/// ```
+pub fn copy_with_progress<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    mut step_progress: StepProgress,
+) -> io::Result<u64>
+where
+    R: Read + ?Sized,
+    W: Write + ?Sized,
+{
+    let mut total = 0;
+
+    loop {
+        let count = io::copy(&mut reader.take(CHUNK_SIZE), writer)?;
+
+        if count == 0 {
+            break;
+        }
+
+        total += count;
+        step_progress.step(count as usize);
+    }
+
+    step_progress.finish();
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::{Cursor, SeekFrom};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::progress::{DisplayMode, WithProgress};
+    use crate::utils::buffer_output;
+
+    /// Data spanning over several chunks.
+    fn data() -> Vec<u8> {
+        (0..2 * CHUNK_SIZE + 100).map(|i| (i % 251) as u8).collect()
+    }
+
+    /// Build a progress bar logging its position, and a function returning
+    /// its last line.
+    fn log_progress() -> (StepProgress, impl Fn() -> String) {
+        let (output_stream, output) = buffer_output();
+
+        let step_progress = StepProgress::new()
+            .with_template("{pos}/{total}".parse().unwrap())
+            .with_display_mode(DisplayMode::Log)
+            .with_refresh_delay(Duration::ZERO)
+            .with_output_stream(output_stream);
+
+        let last_line = move || output().lines().last().unwrap_or_default().to_string();
+
+        (step_progress, last_line)
+    }
+
+    #[test]
+    fn copy_several_chunks() {
+        let data = data();
+        let mut reader = Cursor::new(&data);
+        let mut writer = Vec::new();
+
+        let (step_progress, last_line) = log_progress();
+        let step_progress = step_progress.with_max_step(data.len());
+        let count = copy_with_progress(&mut reader, &mut writer, step_progress).unwrap();
+
+        assert_eq!(count, data.len() as u64);
+        assert!(writer == data);
+        let total = data.len().to_string();
+        assert_eq!(last_line(), format!("{}/{}", total, total));
+    }
+
+    #[test]
+    fn copy_end_of_seeked_file() {
+        let dir = std::env::temp_dir().join(format!("prog_rs-copy-file-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let data = data();
+        fs::write(dir.join("source"), &data).unwrap();
+
+        let mut reader = File::open(dir.join("source")).unwrap();
+        reader.seek(SeekFrom::Start(30)).unwrap();
+        let mut writer = File::create(dir.join("dest")).unwrap();
+
+        let (step_progress, last_line) = log_progress();
+        let count = copy_file_with_progress(&mut reader, &mut writer, step_progress).unwrap();
+
+        assert_eq!(count, data.len() as u64 - 30);
+        assert!(fs::read(dir.join("dest")).unwrap() == data[30..]);
+        let total = (data.len() - 30).to_string();
+        assert_eq!(last_line(), format!("{}/{}", total, total));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn remaining_len_after_seek() {
+        let dir = std::env::temp_dir().join(format!("prog_rs-copy-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("source"), [0; 100]).unwrap();
+
+        // Only the end of an already seeked file is copied
+        let mut reader = File::open(dir.join("source")).unwrap();
+        assert_eq!(remaining_len(&mut reader).unwrap(), 100);
+        reader.seek(SeekFrom::Start(30)).unwrap();
+        assert_eq!(remaining_len(&mut reader).unwrap(), 70);
+        reader.seek(SeekFrom::Start(200)).unwrap();
+        assert_eq!(remaining_len(&mut reader).unwrap(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,393 @@
+//! Defines strategies to estimate the remaining time of a progress bar.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// State of a progress bar, given to an estimator.
+#[derive(Clone, Copy, Debug)]
+pub struct EstimatorState<'a> {
+    /// Current step.
+    pub pos: usize,
+
+    /// Expected max step.
+    pub total: usize,
+
+    /// Time since the progress bar was started.
+    pub elapsed: Duration,
+
+    /// Recent steps, with the instant they were reached, from the oldest to
+    /// the newest. It covers the speed window of the progress bar.
+    pub history: &'a VecDeque<(Instant, usize)>,
+
+    /// Current speed, as displayed, which depends on the speed smoothing.
+    pub rate: f32,
+}
+
+impl EstimatorState<'_> {
+    /// Number of steps remaining.
+    pub fn remaining(&self) -> usize {
+        self.total.saturating_sub(self.pos)
+    }
+}
+
+/// A strategy to estimate the remaining time of a progress bar.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::prelude::*;
+/// use prog_rs::{Estimator, EstimatorState};
+/// use std::time::Duration;
+///
+/// /// Assume each step takes 10ms.
+/// #[derive(Clone, Debug)]
+/// struct Fixed;
+///
+/// impl Estimator for Fixed {
+///     fn estimate(&self, state: &EstimatorState) -> Option<Duration> {
+///         Some(Duration::from_millis(10) * state.remaining() as u32)
+///     }
+/// }
+///
+/// for _ in (0..1_000).progress().with_estimator(Fixed) {}
+/// ```
+pub trait Estimator: EstimatorClone + fmt::Debug + Send + Sync {
+    /// Called each time the progress bar is drawn, with current step.
+    fn record(&mut self, _time: Instant, _step: usize) {}
+
+    /// Estimate the remaining time, if possible.
+    fn estimate(&self, state: &EstimatorState) -> Option<Duration>;
+}
+
+/// Allows to clone boxed estimators, this is implemented for any estimator
+/// which implements `Clone`.
+pub trait EstimatorClone {
+    fn clone_box(&self) -> Box<dyn Estimator>;
+}
+
+impl<T> EstimatorClone for T
+where
+    T: Estimator + Clone + 'static,
+{
+    fn clone_box(&self) -> Box<dyn Estimator> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Estimator> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Exponential moving average of speeds measured over periods of variable
+/// length, the smoothing factor `alpha` is the weight given to the last
+/// second. With a factor of 0, all speeds are weighted by their duration.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct EmaSpeed {
+    sum: f64,
+    weight: f64,
+}
+
+impl EmaSpeed {
+    /// Account for a speed measured over `secs` seconds.
+    pub(crate) fn update(&mut self, alpha: f64, speed: f64, secs: f64) {
+        let alpha = alpha.clamp(0., 1.);
+
+        // Older speeds are never forgotten, which is the limit of the average
+        // for small factors
+        if alpha == 0. {
+            self.sum += secs * speed;
+            self.weight += secs;
+            return;
+        }
+
+        // Computed as `1 - (1 - alpha)^secs` without rounding small factors
+        // to 0
+        let sample_weight = -(secs * (-alpha).ln_1p()).exp_m1();
+        self.sum = (1. - sample_weight) * self.sum + sample_weight * speed;
+        self.weight = (1. - sample_weight) * self.weight + sample_weight;
+    }
+
+    /// Get the average speed, if any speed was measured.
+    pub(crate) fn get(&self) -> Option<f64> {
+        // Weights of all samples don't sum to 1
+        (self.weight > 0.).then(|| self.sum / self.weight)
+    }
+}
+
+/// Get the time required for `remaining` steps at given rate.
+fn time_at_rate(remaining: usize, rate: f64) -> Option<Duration> {
+    if rate > 0. && rate.is_finite() {
+        Duration::try_from_secs_f64(remaining as f64 / rate).ok()
+    } else {
+        None
+    }
+}
+
+//   ____ _       _           _
+//  / ___| | ___ | |__   __ _| |
+// | |  _| |/ _ \| '_ \ / _` | |
+// | |_| | | (_) | |_) | (_| | |
+//  \____|_|\___/|_.__/ \__,_|_|
+//
+
+/// Assume the average speed since the beginning stays the same, this is the
+/// default estimator.
+#[derive(Clone, Debug, Default)]
+pub struct GlobalAverage;
+
+impl Estimator for GlobalAverage {
+    fn estimate(&self, state: &EstimatorState) -> Option<Duration> {
+        Some(Duration::from_secs_f64(
+            state.remaining() as f64 / (1. + state.pos as f64) * state.elapsed.as_secs_f64(),
+        ))
+    }
+}
+
+//  ____  _ _     _ _
+// / ___|| (_) __| (_)_ __   __ _
+// \___ \| | |/ _` | | '_ \ / _` |
+//  ___) | | | (_| | | | | | (_| |
+// |____/|_|_|\__,_|_|_| |_|\__, |
+//                          |___/
+
+/// Assume the speed over the speed window stays the same, the speed is
+/// smoothed as configured with `with_speed_smoothing`.
+#[derive(Clone, Debug, Default)]
+pub struct SlidingWindow;
+
+impl Estimator for SlidingWindow {
+    fn estimate(&self, state: &EstimatorState) -> Option<Duration> {
+        time_at_rate(state.remaining(), state.rate.into())
+    }
+}
+
+//  _____ __  __    _
+// | ____|  \/  |  / \
+// |  _| | |\/| | / _ \
+// | |___| |  | |/ ___ \
+// |_____|_|  |_/_/   \_\
+//
+
+/// Assume the speed stays the same, the speed is an exponential moving
+/// average of speeds measured between two draws, as computed by
+/// `SpeedSmoothing::Ema`. This doesn't depend on the speed smoothing of the
+/// progress bar.
+#[derive(Clone, Debug)]
+pub struct Ema {
+    alpha: f64,
+    last: Option<(Instant, usize)>,
+    speed: EmaSpeed,
+}
+
+impl Ema {
+    /// Create an estimator with given smoothing factor between 0 and 1,
+    /// which is the weight given to the last second: a higher factor gives
+    /// more weight to recent speeds. With a factor of 0, the average speed
+    /// since the first draw is used.
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(0., 1.),
+            last: None,
+            speed: EmaSpeed::default(),
+        }
+    }
+}
+
+impl Default for Ema {
+    fn default() -> Self {
+        Self::new(0.1)
+    }
+}
+
+impl Estimator for Ema {
+    fn record(&mut self, time: Instant, step: usize) {
+        if let Some((last_time, last_step)) = self.last {
+            let secs = (time - last_time).as_secs_f64();
+
+            if secs > 0. {
+                let speed = step.saturating_sub(last_step) as f64 / secs;
+                self.speed.update(self.alpha, speed, secs);
+            }
+        }
+
+        self.last = Some((time, step));
+    }
+
+    fn estimate(&self, state: &EstimatorState) -> Option<Duration> {
+        time_at_rate(state.remaining(), self.speed.get()?)
+    }
+}
+
+//  _     _                         ____
+// | |   (_)_ __   ___  __ _ _ __  |  _ \ ___  __ _
+// | |   | | '_ \ / _ \/ _` | '__| | |_) / _ \/ _` |
+// | |___| | | | |  __/ (_| | |    |  _ <  __/ (_| |
+// |_____|_|_| |_|\___|\__,_|_|    |_| \_\___|\__, |
+//                                            |___/
+
+/// Fit a line through the steps of the speed window using least squares,
+/// and find when it reaches the expected max step. This doesn't depend on
+/// the speed smoothing of the progress bar.
+#[derive(Clone, Debug, Default)]
+pub struct LinearRegression;
+
+impl Estimator for LinearRegression {
+    fn estimate(&self, state: &EstimatorState) -> Option<Duration> {
+        let now = Instant::now();
+        let &(origin, _) = state.history.front()?;
+
+        let points: Vec<(f64, f64)> = state
+            .history
+            .iter()
+            .copied()
+            .chain(std::iter::once((now, state.pos)))
+            .map(|(time, step)| ((time - origin).as_secs_f64(), step as f64))
+            .collect();
+
+        let count = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+        let cov: f64 = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let var: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+
+        if var <= 0. {
+            return None;
+        }
+
+        let slope = cov / var;
+        let intercept = mean_y - slope * mean_x;
+
+        if slope <= 0. {
+            return None;
+        }
+
+        let end = (state.total as f64 - intercept) / slope;
+        let now = (now - origin).as_secs_f64();
+        Duration::try_from_secs_f64((end - now).max(0.)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::step_progress::StepProgress;
+
+    /// Build a history where 10 steps are made each second, during the last
+    /// 10 seconds.
+    fn steady_history() -> VecDeque<(Instant, usize)> {
+        let start = Instant::now() - Duration::from_secs(10);
+
+        (0..=10)
+            .map(|i| (start + Duration::from_secs(i), 10 * i as usize))
+            .collect()
+    }
+
+    fn state(history: &VecDeque<(Instant, usize)>, rate: f32) -> EstimatorState<'_> {
+        EstimatorState {
+            pos: 100,
+            total: 200,
+            elapsed: Duration::from_secs(10),
+            history,
+            rate,
+        }
+    }
+
+    fn assert_secs(eta: Option<Duration>, expected: f64) {
+        let eta = eta.expect("no estimation").as_secs_f64();
+        assert!((eta - expected).abs() < 0.01, "{} != {}", eta, expected);
+    }
+
+    #[test]
+    fn global_average() {
+        let history = steady_history();
+        assert_secs(GlobalAverage.estimate(&state(&history, 0.)), 9.9);
+    }
+
+    #[test]
+    fn sliding_window() {
+        let history = steady_history();
+        assert_secs(SlidingWindow.estimate(&state(&history, 20.)), 5.);
+        assert_eq!(SlidingWindow.estimate(&state(&history, 0.)), None);
+    }
+
+    #[test]
+    fn ema_speed_clamps_alpha() {
+        for alpha in [-1., 2., f64::INFINITY] {
+            let mut speed = EmaSpeed::default();
+            speed.update(alpha, 10., 0.5);
+            speed.update(alpha, 20., 0.5);
+            assert!(speed.get().is_none_or(f64::is_finite));
+        }
+
+        let mut speed = EmaSpeed::default();
+        speed.update(2., 10., 0.5);
+        speed.update(2., 20., 0.5);
+        assert_eq!(speed.get(), Some(20.));
+    }
+
+    #[test]
+    fn ema_speed_without_decay() {
+        // All speeds are weighted by their duration
+        let mut speed = EmaSpeed::default();
+        speed.update(0., 10., 1.);
+        speed.update(0., 40., 2.);
+        assert_eq!(speed.get(), Some(30.));
+
+        // Which is the limit for small factors
+        let mut speed = EmaSpeed::default();
+        speed.update(1e-20, 10., 1.);
+        speed.update(1e-20, 40., 2.);
+        assert!((speed.get().unwrap() - 30.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ema() {
+        let history = steady_history();
+        let start = Instant::now();
+        let mut ema = Ema::new(0.5);
+        assert_eq!(ema.estimate(&state(&history, 0.)), None);
+
+        // Speeds of 10 then 20 steps per second, the average is corrected
+        // for the weight of the missing history
+        ema.record(start, 0);
+        ema.record(start + Duration::from_secs(1), 10);
+        ema.record(start + Duration::from_secs(2), 30);
+        assert_secs(ema.estimate(&state(&history, 0.)), 6.);
+
+        // Two seconds weigh more than one second
+        let mut ema = Ema::new(0.5);
+        ema.record(start, 0);
+        ema.record(start + Duration::from_secs(1), 10);
+        ema.record(start + Duration::from_secs(3), 50);
+        assert_secs(ema.estimate(&state(&history, 0.)), 70. / 13.);
+
+        // Without decay, this is the average speed
+        let mut ema = Ema::new(0.);
+        ema.record(start, 0);
+        ema.record(start + Duration::from_secs(1), 10);
+        ema.record(start + Duration::from_secs(3), 50);
+        assert_secs(ema.estimate(&state(&history, 0.)), 6.);
+    }
+
+    #[test]
+    fn linear_regression() {
+        let history = steady_history();
+        assert_secs(LinearRegression.estimate(&state(&history, 0.)), 10.);
+
+        let stalled = (0..10).map(|i| (history[i].0, 100)).collect();
+        assert_eq!(LinearRegression.estimate(&state(&stalled, 0.)), None);
+    }
+
+    #[test]
+    fn progress_bars_are_sync() {
+        fn is_sync<T: Send + Sync>() {}
+        is_sync::<Box<dyn Estimator>>();
+        is_sync::<StepProgress>();
+    }
+}
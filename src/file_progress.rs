@@ -1,17 +1,28 @@
 //! Defines a wrapper around files to display a progress bar.
 
+use crate::read_progress::ReadProgress;
 use crate::step_progress::{StepProgress, WithStepProgress};
 
 use std::convert::TryInto;
 use std::fs::File;
-use std::io;
+use std::io::{self, BufReader};
 
-/// A wrapper read only stream arround a file.
+/// Get the size of a file from its metadata.
+fn file_size(file: &File) -> usize {
+    let size = file.metadata().map_or(0, |m| m.len());
+    size.try_into().expect("file size doesn't fit in usize")
+}
+
+/// A wrapper read only stream arround a file, its total size is read from
+/// the file's metadata.
 ///
 /// # Example
 ///
-/// ```
+/// ```no_run
 /// use prog_rs::prelude::*;
+/// # use prog_rs::BarPosition;
+/// # use std::fs::File;
+/// # use std::io::{BufRead, BufReader};
 ///
 /// let f = File::open("../../data/addresses/bano.csv")
 ///     .unwrap()
@@ -23,63 +34,163 @@ use std::io;
 /// ```
 #[derive(Debug)]
 pub struct FileProgress {
-    inner: File,
-    step_progress: StepProgress,
+    inner: ReadProgress<File>,
 }
 
 impl FileProgress {
-    fn new(inner: File) -> Self {
-        let max_step = inner.metadata().map_or(0, |m| m.len());
+    /// Wrap a file, using its size as the total.
+    pub fn new(inner: File) -> Self {
+        let max_step = file_size(&inner);
 
         Self {
-            inner,
-            step_progress: StepProgress::new()
-                .with_humanize(true)
-                .with_unit("B")
-                .with_max_step(max_step.try_into().expect("file size doesn't fit in usize")),
+            inner: ReadProgress::new(inner).with_max_step(max_step),
         }
     }
+
+    /// Count all processed bytes instead of tracking the position in the
+    /// file, see `ReadProgress::with_processed_bytes`.
+    pub fn with_processed_bytes(self, processed_bytes: bool) -> Self {
+        Self {
+            inner: self.inner.with_processed_bytes(processed_bytes),
+        }
+    }
+
+    /// Get a reference to the wrapped file.
+    pub fn get_ref(&self) -> &File {
+        self.inner.get_ref()
+    }
+
+    /// Unwrap the file.
+    pub fn into_inner(self) -> File {
+        self.inner.into_inner()
+    }
 }
 
 impl io::Seek for FileProgress {
     fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
-        let res = self.inner.seek(pos);
-
-        if let Ok(new_pos) = res {
-            let cur_step: u64 = self
-                .step_progress
-                .cur_step()
-                .try_into()
-                .expect("file size doesn't fit in usize");
-
-            if new_pos > cur_step {
-                self.step_progress.step((new_pos - cur_step) as usize);
-            }
-        }
-
-        res
+        self.inner.seek(pos)
     }
 }
 
 impl io::Read for FileProgress {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let res = self.inner.read(buf);
-
-        if let Ok(step) = res {
-            if step == 0 {
-                self.step_progress.finish();
-            } else {
-                self.step_progress.step(step);
-            }
-        }
+        self.inner.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
 
-        res
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.inner.read_to_end(buf)
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.inner.read_to_string(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.inner.read_exact(buf)
     }
 }
 
 impl WithStepProgress for FileProgress {
     fn get_step_progress(&mut self) -> &mut StepProgress {
-        &mut self.step_progress
+        self.inner.get_step_progress()
+    }
+}
+
+/// A buffered wrapper read only stream arround a file, which can be used to
+/// iterate over lines of the file without adding another buffer.
+///
+/// # Example
+///
+/// ```no_run
+/// use prog_rs::prelude::*;
+/// # use std::fs::File;
+/// # use std::io::BufRead;
+///
+/// let f = File::open("../../data/addresses/bano.csv")
+///     .unwrap()
+///     .buffered_progress()
+///     .with_prefix(" Read file ...");
+/// println!("This file has {} lines", f.lines().count());
+/// ```
+#[derive(Debug)]
+pub struct BufFileProgress {
+    inner: ReadProgress<BufReader<File>>,
+}
+
+impl BufFileProgress {
+    /// Wrap a file with a buffer, using its size as the total.
+    pub fn new(inner: File) -> Self {
+        let max_step = file_size(&inner);
+
+        Self {
+            inner: ReadProgress::new(BufReader::new(inner)).with_max_step(max_step),
+        }
+    }
+
+    /// Count all processed bytes instead of tracking the position in the
+    /// file, see `ReadProgress::with_processed_bytes`.
+    pub fn with_processed_bytes(self, processed_bytes: bool) -> Self {
+        Self {
+            inner: self.inner.with_processed_bytes(processed_bytes),
+        }
+    }
+
+    /// Get a reference to the wrapped file.
+    pub fn get_ref(&self) -> &File {
+        self.inner.get_ref().get_ref()
+    }
+
+    /// Unwrap the file, data remaining in the buffer is lost.
+    pub fn into_inner(self) -> File {
+        self.inner.into_inner().into_inner()
+    }
+}
+
+impl io::Seek for BufFileProgress {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl io::Read for BufFileProgress {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.inner.read_to_end(buf)
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.inner.read_to_string(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.inner.read_exact(buf)
+    }
+}
+
+impl io::BufRead for BufFileProgress {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl WithStepProgress for BufFileProgress {
+    fn get_step_progress(&mut self) -> &mut StepProgress {
+        self.inner.get_step_progress()
     }
 }
 
@@ -92,10 +203,64 @@ impl WithStepProgress for FileProgress {
 
 pub trait AsFileProgress {
     fn progress(self) -> FileProgress;
+    fn buffered_progress(self) -> BufFileProgress;
 }
 
 impl AsFileProgress for File {
     fn progress(self) -> FileProgress {
         FileProgress::new(self)
     }
+
+    fn buffered_progress(self) -> BufFileProgress {
+        BufFileProgress::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::prelude::*;
+
+    use super::*;
+    use crate::progress::WithProgress;
+    use crate::utils::sink_output;
+
+    #[test]
+    fn total_is_file_size() {
+        let path = std::env::temp_dir().join(format!("prog_rs-file-{}", std::process::id()));
+        std::fs::write(&path, [0; 100]).unwrap();
+
+        let mut file =
+            FileProgress::new(File::open(&path).unwrap()).with_output_stream(sink_output());
+        assert_eq!(file.max_step(), Some(100));
+
+        file.read_exact(&mut [0; 30]).unwrap();
+        assert_eq!(file.cur_step(), 30);
+
+        let mut file = File::open(&path).unwrap().buffered_progress();
+        assert_eq!(file.max_step(), Some(100));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn count_consumed_lines() {
+        let path = std::env::temp_dir().join(format!("prog_rs-lines-{}", std::process::id()));
+        std::fs::write(&path, "first\nsecond\nthird\n").unwrap();
+
+        let mut file = File::open(&path)
+            .unwrap()
+            .buffered_progress()
+            .with_template("{pos}/{total} {eta}".parse().unwrap())
+            .with_humanize(false)
+            .with_output_stream(sink_output());
+
+        file.read_line(&mut String::new()).unwrap();
+        assert_eq!(file.cur_step(), 6);
+        assert_ne!(file.get_step_progress().render(80), "6B/19B ");
+
+        // The estimated time is no longer displayed once finished
+        assert_eq!(file.by_ref().lines().count(), 2);
+        assert_eq!(file.cur_step(), 19);
+        assert_eq!(file.get_step_progress().render(80), "19B/19B ");
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -1,6 +1,7 @@
 //! Defines a wrapper around iterators to display a progress bar.
 
 use crate::step_progress::{StepProgress, WithStepProgress};
+use crate::utils::expected_len;
 
 /// A wrapper iterator arround another iterator which adds a progress bar.
 ///
@@ -8,6 +9,7 @@ use crate::step_progress::{StepProgress, WithStepProgress};
 ///
 /// ```
 /// use prog_rs::prelude::*;
+/// # fn do_something() {}
 ///
 /// for _ in (0..1_000)
 ///     .progress()
@@ -32,11 +34,15 @@ where
     I: Iterator<Item = E>,
 {
     fn new(inner: I) -> Self {
-        let (max_step, _) = inner.size_hint();
+        let mut step_progress = StepProgress::new();
+
+        if let Some(max_step) = expected_len(inner.size_hint()) {
+            step_progress.set_max_step(max_step);
+        }
 
         Self {
             inner,
-            step_progress: StepProgress::new().with_max_step(max_step),
+            step_progress,
         }
     }
 }
@@ -53,8 +59,18 @@ where
         match item {
             None => self.step_progress.finish(),
             Some(_) => {
-                let new_max_step = self.step_progress.cur_step() + self.inner.size_hint().0 + 1;
-                self.step_progress.set_max_step(new_max_step);
+                let cur_step = self.step_progress.cur_step();
+
+                match expected_len(self.inner.size_hint()) {
+                    Some(remaining) => self.step_progress.set_max_step(cur_step + remaining + 1),
+                    // A previous size hint may have underestimated the total
+                    None => {
+                        if let Some(max_step) = self.step_progress.max_step() {
+                            self.step_progress.set_max_step(max_step.max(cur_step + 1));
+                        }
+                    }
+                }
+
                 self.step_progress.step(1)
             }
         }
@@ -94,3 +110,24 @@ where
         IterProgress::new(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::WithProgress;
+    use crate::utils::sink_output;
+
+    #[test]
+    fn total_is_never_below_current_step() {
+        let mut iter = (0..10)
+            .chain((0..10).filter(|x| x % 2 == 0))
+            .progress()
+            .with_output_stream(sink_output());
+
+        while iter.next().is_some() {
+            assert!(iter.cur_step() <= iter.max_step().unwrap());
+        }
+
+        assert_eq!((iter.cur_step(), iter.max_step()), (15, Some(15)));
+    }
+}
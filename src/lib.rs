@@ -22,6 +22,7 @@
 //!
 //! ```
 //! use prog_rs::prelude::*;
+//! # fn do_something() {}
 //!
 //! for _ in (0..1_000)
 //!     .progress()
@@ -33,10 +34,14 @@
 //! }
 //! ```
 //!
-//! This same behaviour is also implemented for files:
+//! This same behaviour is also implemented for files, any other reader can
+//! be wrapped using `ReadProgress::new`:
 //!
-//! ```
+//! ```no_run
 //! use prog_rs::prelude::*;
+//! # use prog_rs::BarPosition;
+//! # use std::fs::File;
+//! # use std::io::{BufRead, BufReader};
 //!
 //! let f = File::open("../../data/addresses/bano.csv")
 //!     .unwrap()
@@ -47,6 +52,9 @@
 //! println!("This file has {} lines", f.lines().count());
 //! ```
 //!
+//! Use `.buffered_progress()` instead to get a reader which implements
+//! `BufRead` without wrapping it in another buffer.
+//!
 //!
 //! Advanced usage
 //! --------------
@@ -55,6 +63,10 @@
 //! outside of the iterators wrapper:
 //!
 //! ```
+//! # use prog_rs::prelude::*;
+//! # use prog_rs::{OutputStream, Progress};
+//! # use std::thread::sleep;
+//! # use std::time::Duration;
 //! let mut progress = Progress::new()
 //!     .with_bar_width(30)
 //!     .with_extra_infos("Hello, World!")
@@ -71,6 +83,50 @@
 //! progress.finished().ok();
 //! ```
 //!
+//! Several progress bars can be displayed at once by attaching them to a
+//! `MultiProgress`, which is useful when working with threads:
+//!
+//! ```
+//! use prog_rs::prelude::*;
+//! use prog_rs::MultiProgress;
+//!
+//! let multi = MultiProgress::new();
+//! let first = multi.add((0..1_000).progress().with_prefix("First"));
+//! let second = multi.add((0..1_000).progress().with_prefix("Second"));
+//!
+//! let handle = std::thread::spawn(move || first.for_each(|_| ()));
+//! second.for_each(|_| ());
+//! handle.join().unwrap();
+//! ```
+//!
+//! Text can be printed above the progress bars currently displayed using
+//! `prog_rs::println`, or the `println` method of a progress bar:
+//!
+//! ```
+//! use prog_rs::prelude::*;
+//!
+//! let mut iter = (0..1_000).progress();
+//!
+//! while let Some(i) = iter.next() {
+//!     if i % 100 == 0 {
+//!         iter.println(format!("Reached {}", i)).unwrap();
+//!     }
+//! }
+//! ```
+//!
+//!
+//! Optional features
+//! -----------------
+//!
+//! - `futures`: adds `.progress()` to streams.
+//! - `futures-io`: implements `futures::io` traits for `AsyncProgress`.
+//! - `log`: adds `ProgressLogger`, a logger printing records above progress
+//!   bars.
+//! - `rayon`: adds `.progress()` to parallel iterators.
+//! - `tokio`: implements `tokio::io` traits for `AsyncProgress`.
+//! - `tracing`: adds `ProgressLayer`, a `tracing-subscriber` layer displaying
+//!   progress bars for spans.
+//!
 //!
 //! Performances
 //! ------------
@@ -93,15 +149,56 @@
 
 extern crate term_size;
 
+mod screen;
+mod ticker;
 mod utils;
 
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub mod async_progress;
+pub mod concat_progress;
+pub mod copy_progress;
+pub mod estimator;
 pub mod file_progress;
 pub mod iter_progress;
+#[cfg(feature = "log")]
+pub mod logger;
+pub mod multi_progress;
+#[cfg(feature = "rayon")]
+pub mod par_iter_progress;
 pub mod prelude;
 pub mod progress;
+pub mod read_progress;
+pub mod shared_progress;
 pub mod step_progress;
+#[cfg(feature = "futures")]
+pub mod stream_progress;
+pub mod style;
+pub mod template;
+#[cfg(feature = "tracing")]
+pub mod tracing_layer;
+pub mod write_progress;
 
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub use async_progress::*;
+pub use concat_progress::*;
+pub use copy_progress::*;
+pub use estimator::*;
 pub use file_progress::*;
 pub use iter_progress::*;
+#[cfg(feature = "log")]
+pub use logger::*;
+pub use multi_progress::*;
+#[cfg(feature = "rayon")]
+pub use par_iter_progress::*;
 pub use progress::*;
+pub use read_progress::*;
+pub use screen::{println, suspend};
+pub use shared_progress::*;
 pub use step_progress::*;
+#[cfg(feature = "futures")]
+pub use stream_progress::*;
+pub use style::*;
+pub use template::*;
+#[cfg(feature = "tracing")]
+pub use tracing_layer::*;
+pub use write_progress::*;
@@ -0,0 +1,165 @@
+//! Defines a logger which prints records above progress bars, requires the
+//! `log` feature.
+
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::progress::OutputStream;
+use crate::screen;
+
+/// A logger which prints records above the progress bars currently
+/// displayed, and draws them again below.
+///
+/// # Example
+///
+/// ```
+/// use log::LevelFilter;
+/// use prog_rs::prelude::*;
+/// use prog_rs::ProgressLogger;
+///
+/// ProgressLogger::new()
+///     .with_level(LevelFilter::Warn)
+///     .init()
+///     .unwrap();
+///
+/// for i in (0..1_000).progress() {
+///     if i % 100 == 0 {
+///         log::warn!("Bad record #{}", i);
+///     }
+/// }
+/// ```
+pub struct ProgressLogger {
+    inner: Option<Box<dyn Log>>,
+    level: LevelFilter,
+    output_stream: OutputStream,
+}
+
+impl ProgressLogger {
+    /// Create a logger printing records of any level on standard error.
+    pub fn new() -> Self {
+        Self {
+            inner: None,
+            level: LevelFilter::Trace,
+            output_stream: OutputStream::StdErr,
+        }
+    }
+
+    /// Create a logger forwarding records to another logger, progress bars
+    /// are hidden while it runs.
+    pub fn wrap<L>(inner: L) -> Self
+    where
+        L: Log + 'static,
+    {
+        Self {
+            inner: Some(Box::new(inner)),
+            ..Self::new()
+        }
+    }
+
+    /// Change the maximal level of records that are logged.
+    pub fn with_level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Change the stream records are printed in, this is ignored if records
+    /// are forwarded to another logger.
+    pub fn with_output_stream(mut self, output_stream: OutputStream) -> Self {
+        self.output_stream = output_stream;
+        self
+    }
+
+    /// Set this logger as the global logger.
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let level = self.level;
+        log::set_boxed_logger(Box::new(self)).map(|()| log::set_max_level(level))
+    }
+}
+
+impl Default for ProgressLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Log for ProgressLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+            && self
+                .inner
+                .as_ref()
+                .map_or(true, |inner| inner.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        match &self.inner {
+            Some(inner) => screen::suspend(|| inner.log(record)),
+            None => {
+                let text = format!(
+                    "[{:<5} {}] {}",
+                    record.level(),
+                    record.target(),
+                    record.args()
+                );
+
+                screen::lock().println(&self.output_stream, &text).ok();
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(inner) = &self.inner {
+            inner.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::Level;
+
+    use super::*;
+    use crate::progress::{DisplayMode, Progress, WithProgress};
+    use crate::utils::buffer_output;
+
+    #[test]
+    fn print_above_progress_bar() {
+        let (output_stream, output) = buffer_output();
+
+        let logger = ProgressLogger::new()
+            .with_level(LevelFilter::Info)
+            .with_output_stream(output_stream.clone());
+
+        let mut progress = Progress::new()
+            .with_template("{prefix} {percent}".parse().unwrap())
+            .with_prefix("Loading")
+            .with_display_mode(DisplayMode::Terminal)
+            .with_output_stream(output_stream);
+
+        progress.update(0.5).unwrap();
+
+        let log = |level, message| {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("{}", message))
+                    .level(level)
+                    .target("test")
+                    .build(),
+            )
+        };
+
+        // The progress bar is erased and drawn again below the record
+        log(Level::Warn, "first");
+        assert!(output().ends_with("\r\x1b[J[WARN  test] first\nLoading 50.0%"));
+
+        // Records above the level are ignored
+        let len = output().len();
+        log(Level::Debug, "second");
+        assert_eq!(output().len(), len);
+
+        progress.finished().unwrap();
+    }
+}
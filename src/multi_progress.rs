@@ -0,0 +1,220 @@
+//! Defines a container displaying several progress bars at once.
+
+use std::io;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::progress::{OutputStream, WithProgress};
+use crate::screen::{self, BlockOwner};
+
+/// A container which owns a region of the terminal and draws its children
+/// progress bars as stacked lines.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::prelude::*;
+/// use prog_rs::MultiProgress;
+///
+/// let multi = MultiProgress::new();
+///
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let iter = multi.add((0..1_000).progress().with_prefix(format!("Worker #{}", i)));
+///         std::thread::spawn(move || iter.for_each(|_| ()))
+///     })
+///     .collect();
+///
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiProgress {
+    state: Arc<Mutex<MultiState>>,
+}
+
+impl MultiProgress {
+    /// Create a new empty container, displayed on standard output.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MultiState::default())),
+        }
+    }
+
+    /// Change the output stream the progress bars are displayed in. This
+    /// overrides the output stream of each child.
+    pub fn with_output_stream(self, output_stream: OutputStream) -> Self {
+        self.state.lock().unwrap().output_stream = output_stream;
+        self
+    }
+
+    /// Attach a progress bar to this container, it will then be displayed
+    /// below the progress bars that were previously added.
+    pub fn add<P: WithProgress>(&self, mut progress: P) -> P {
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.lines.push(Line {
+                id,
+                text: String::new(),
+                finished: false,
+            });
+            id
+        };
+
+        progress.get_progress().set_multi_slot(Some(MultiSlot {
+            state: self.state.clone(),
+            id,
+        }));
+
+        progress
+    }
+
+    /// Detach a progress bar from this container, its line is erased and it
+    /// will be displayed on its own output stream again.
+    pub fn remove<P: WithProgress>(&self, progress: &mut P) -> io::Result<()> {
+        let id = match progress.get_progress().multi_slot() {
+            Some(slot) if Arc::ptr_eq(&slot.state, &self.state) => slot.id,
+            _ => return Ok(()),
+        };
+
+        // The line is removed before the slot is dropped, so that it is not
+        // kept on screen
+        let mut state = self.state.lock().unwrap();
+        state.lines.retain(|line| line.id != id);
+        drop(state);
+
+        progress.get_progress().set_multi_slot(None);
+        self.state.lock().unwrap().refresh()
+    }
+}
+
+impl Default for MultiProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//  ____  _       _
+// / ___|| | ___ | |_
+// \___ \| |/ _ \| __|
+//  ___) | | (_) | |_
+// |____/|_|\___/ \__|
+//
+
+/// The line owned by a progress bar inside of a `MultiProgress`. It is
+/// shared by copies of the progress bar, and considered finished once they
+/// are all dropped.
+#[derive(Debug)]
+pub(crate) struct MultiSlot {
+    state: Arc<Mutex<MultiState>>,
+    id: usize,
+}
+
+impl MultiSlot {
+    /// Get the stream the region is displayed in.
+    pub(crate) fn output_stream(&self) -> OutputStream {
+        self.state.lock().unwrap().output_stream.clone()
+    }
+
+    /// Replace the text of this line and redraw the whole region.
+    pub(crate) fn draw(&self, text: String, finished: bool) -> io::Result<()> {
+        self.state
+            .lock()
+            .unwrap()
+            .update(self.id, Some(text), finished)
+    }
+}
+
+impl Drop for MultiSlot {
+    /// The line of a progress bar dropped before being finished is left as
+    /// it is, so that the region can still be released.
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let unfinished = state
+            .lines
+            .iter()
+            .any(|line| line.id == self.id && !line.finished);
+
+        if unfinished {
+            state.update(self.id, None, true).ok();
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Line {
+    id: usize,
+    text: String,
+    finished: bool,
+}
+
+#[derive(Debug)]
+struct MultiState {
+    lines: Vec<Line>,
+    next_id: usize,
+    output_stream: OutputStream,
+    owner: BlockOwner,
+}
+
+impl Default for MultiState {
+    fn default() -> Self {
+        Self {
+            lines: Vec::new(),
+            next_id: 0,
+            output_stream: OutputStream::StdOut,
+            owner: BlockOwner::default(),
+        }
+    }
+}
+
+impl MultiState {
+    /// Update a line, its text is kept if none is given, and redraw the
+    /// whole region.
+    fn update(&mut self, id: usize, text: Option<String>, finished: bool) -> io::Result<()> {
+        if let Some(line) = self.lines.iter_mut().find(|line| line.id == id) {
+            if let Some(text) = text {
+                line.text = text;
+            }
+
+            line.finished = finished;
+        }
+
+        self.refresh()
+    }
+
+    /// Redraw the whole region, which is released once all progress bars
+    /// are done.
+    fn refresh(&mut self) -> io::Result<()> {
+        let all_finished = self.lines.iter().all(|line| line.finished);
+        self.redraw(all_finished)?;
+
+        if all_finished {
+            self.lines.clear();
+        }
+
+        Ok(())
+    }
+
+    fn redraw(&mut self, finished: bool) -> io::Result<()> {
+        let mut text = String::new();
+
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 {
+                text.push('\n');
+            }
+
+            text.push_str(&line.text);
+        }
+
+        // An empty region is only erased, if it was ever drawn
+        if text.is_empty() && !self.owner.drew() {
+            return Ok(());
+        }
+
+        let finished = finished && !text.is_empty();
+        screen::lock_for_draw().draw(&mut self.owner, &self.output_stream, &text, finished)
+    }
+}
@@ -0,0 +1,184 @@
+//! Defines a wrapper around parallel iterators to display a progress bar,
+//! requires the `rayon` feature.
+
+use rayon::iter::plumbing::{Consumer, ProducerCallback, UnindexedConsumer};
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+use crate::shared_progress::SharedProgress;
+use crate::step_progress::{StepProgress, WithStepProgress};
+
+/// A wrapper parallel iterator arround another parallel iterator which adds
+/// a progress bar, items yielded by all threads are counted.
+///
+/// An item is counted as soon as it is handed to the next stage of the
+/// chain, before this stage processes it. Place `.progress()` after the
+/// expensive stages, otherwise the progress bar may be complete while the
+/// last items are still being processed.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::prelude::*;
+/// use rayon::prelude::*;
+///
+/// let sum: u64 = (0..1_000_000u64)
+///     .into_par_iter()
+///     .map(|x| x * x)
+///     .progress()
+///     .with_prefix("Computing ...")
+///     .sum();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParallelProgress<I> {
+    inner: I,
+    step_progress: StepProgress,
+}
+
+impl<I> ParallelProgress<I>
+where
+    I: ParallelIterator,
+{
+    fn new(inner: I) -> Self {
+        let mut step_progress = StepProgress::new();
+
+        if let Some(max_step) = inner.opt_len() {
+            step_progress.set_max_step(max_step);
+        }
+
+        Self {
+            inner,
+            step_progress,
+        }
+    }
+}
+
+impl<I> ParallelIterator for ParallelProgress<I>
+where
+    I: ParallelIterator,
+{
+    type Item = I::Item;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let progress = SharedProgress::new(self.step_progress);
+        let result = self
+            .inner
+            .inspect(|_| progress.inc(1))
+            .drive_unindexed(consumer);
+        progress.finish();
+        result
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.inner.opt_len()
+    }
+}
+
+impl<I> IndexedParallelIterator for ParallelProgress<I>
+where
+    I: IndexedParallelIterator,
+{
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        let progress = SharedProgress::new(self.step_progress);
+        let result = self.inner.inspect(|_| progress.inc(1)).drive(consumer);
+        progress.finish();
+        result
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        let progress = SharedProgress::new(self.step_progress);
+        let result = self
+            .inner
+            .inspect(|_| progress.inc(1))
+            .with_producer(callback);
+        progress.finish();
+        result
+    }
+}
+
+impl<I> WithStepProgress for ParallelProgress<I> {
+    fn get_step_progress(&mut self) -> &mut StepProgress {
+        &mut self.step_progress
+    }
+}
+
+//  _____                      _____          _ _
+// |  ___| __ ___  _ __ ___   |_   _| __ __ _(_) |_
+// | |_ | '__/ _ \| '_ ` _ \    | || '__/ _` | | __|
+// |  _|| | | (_) | | | | | |   | || | | (_| | | |_
+// |_|  |_|  \___/|_| |_| |_|   |_||_|  \__,_|_|\__|
+//
+
+pub trait AsParallelProgress<I>
+where
+    I: ParallelIterator,
+{
+    fn progress(self) -> ParallelProgress<I>;
+}
+
+impl<I> AsParallelProgress<I> for I
+where
+    I: ParallelIterator,
+{
+    fn progress(self) -> ParallelProgress<I> {
+        ParallelProgress::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::prelude::*;
+
+    use super::*;
+    use crate::progress::{DisplayMode, WithProgress};
+    use crate::utils::buffer_output;
+
+    /// Run `f` with a progress bar over 1000 items, and get the last line
+    /// it printed.
+    fn last_line<F>(f: F) -> String
+    where
+        F: FnOnce(ParallelProgress<rayon::range::Iter<usize>>),
+    {
+        let (output_stream, output) = buffer_output();
+
+        f((0..1_000)
+            .into_par_iter()
+            .progress()
+            .with_template("{pos}/{total} {percent}".parse().unwrap())
+            .with_display_mode(DisplayMode::Log)
+            .with_output_stream(output_stream));
+
+        output().lines().last().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn count_all_items() {
+        // Through `drive_unindexed`
+        let line = last_line(|iter| assert_eq!(iter.filter(|i| i % 2 == 0).count(), 500));
+        assert_eq!(line, "1000/1000 100.0%");
+
+        // Through `drive`
+        let line = last_line(|iter| {
+            let mut items = Vec::new();
+            iter.collect_into_vec(&mut items);
+            assert_eq!(items.len(), 1_000);
+        });
+        assert_eq!(line, "1000/1000 100.0%");
+
+        // Through `with_producer`
+        let line = last_line(|iter| assert_eq!(iter.enumerate().map(|(i, _)| i).max(), Some(999)));
+        assert_eq!(line, "1000/1000 100.0%");
+    }
+}
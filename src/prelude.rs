@@ -2,5 +2,9 @@
 
 pub use crate::file_progress::AsFileProgress;
 pub use crate::iter_progress::AsProgressIterator;
+#[cfg(feature = "rayon")]
+pub use crate::par_iter_progress::AsParallelProgress;
 pub use crate::progress::WithProgress;
 pub use crate::step_progress::WithStepProgress;
+#[cfg(feature = "futures")]
+pub use crate::stream_progress::AsStreamProgress;
@@ -1,11 +1,23 @@
 //! Defines a basic progress bar that needs to be manually updated.
 
 use std::boxed::Box;
-use std::cmp::min;
+use std::cmp::{max, min};
+use std::fmt;
+use std::fs::OpenOptions;
 use std::io;
 use std::io::prelude::*;
+use std::io::IsTerminal;
+use std::mem;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
+use crate::multi_progress::MultiSlot;
+use crate::screen::{self, BlockOwner};
+use crate::style::{ColorMode, Style};
+use crate::template::{Chunk, Field, Template};
+use crate::ticker::Ticker;
+use crate::utils::{convert_to_unit, text_width, truncate};
+
 //   ____             __ _
 //  / ___|___  _ __  / _(_) __ _
 // | |   / _ \| '_ \| |_| |/ _` |
@@ -24,38 +36,213 @@ pub enum BarPosition {
     Left,
 }
 
-/// Available streams to display in.
+/// Different ways of displaying the progress in the output stream.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisplayMode {
+    /// Use `Terminal` if the output stream is a terminal and `Log` otherwise.
+    Auto,
+
+    /// The line is redrawn in place.
+    Terminal,
+
+    /// Complete lines are periodically printed, which is suited for log
+    /// files.
+    Log,
+}
+
+/// Available streams to display in.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum OutputStream {
     /// Standart output.
     StdOut,
 
     /// Standart Error.
     StdErr,
+
+    /// A custom writer, which is shared by all clones of the progress bar.
+    Writer(SharedWriter),
 }
 
 impl OutputStream {
-    fn get(self) -> Box<dyn Write> {
+    /// Display in a custom writer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use prog_rs::prelude::*;
+    /// use prog_rs::OutputStream;
+    ///
+    /// let log = std::fs::File::create("/tmp/progress.log").unwrap();
+    ///
+    /// for _ in (0..1_000)
+    ///     .progress()
+    ///     .with_output_stream(OutputStream::writer(log))
+    /// {}
+    /// ```
+    pub fn writer<W>(writer: W) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        Self::shared(Arc::new(Mutex::new(writer)))
+    }
+
+    /// Display in a custom writer which can still be accessed from outside,
+    /// for example to inspect an in-memory buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use prog_rs::prelude::*;
+    /// use prog_rs::OutputStream;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let buffer = Arc::new(Mutex::new(Vec::new()));
+    ///
+    /// for _ in (0..1_000)
+    ///     .progress()
+    ///     .with_output_stream(OutputStream::shared(buffer.clone()))
+    /// {}
+    ///
+    /// assert!(!buffer.lock().unwrap().is_empty());
+    /// ```
+    pub fn shared<W>(writer: Arc<Mutex<W>>) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        OutputStream::Writer(SharedWriter {
+            inner: writer,
+            is_terminal: false,
+        })
+    }
+
+    /// Display in the controlling terminal of the process, which is still
+    /// available when both standart output and standart error are
+    /// redirected.
+    pub fn tty() -> io::Result<Self> {
+        #[cfg(windows)]
+        let path = "CONOUT$";
+        #[cfg(not(windows))]
+        let path = "/dev/tty";
+
+        let file = OpenOptions::new().write(true).open(path)?;
+        let is_terminal = file.is_terminal();
+
+        Ok(OutputStream::Writer(SharedWriter {
+            inner: Arc::new(Mutex::new(file)),
+            is_terminal,
+        }))
+    }
+
+    pub(crate) fn get(&self) -> Box<dyn Write> {
         use OutputStream::*;
         match self {
             StdOut => Box::new(io::stdout()),
             StdErr => Box::new(io::stderr()),
+            Writer(writer) => Box::new(writer.clone()),
+        }
+    }
+
+    /// Check if this stream is displayed in a terminal.
+    pub fn is_terminal(&self) -> bool {
+        use OutputStream::*;
+        match self {
+            StdOut => io::stdout().is_terminal(),
+            StdErr => io::stderr().is_terminal(),
+            Writer(writer) => writer.is_terminal,
         }
     }
 }
 
+/// A writer that can be shared between several progress bars.
+#[derive(Clone)]
+pub struct SharedWriter {
+    inner: Arc<Mutex<dyn Write + Send>>,
+    is_terminal: bool,
+}
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.lock().unwrap().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.inner.lock().unwrap().write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.lock().unwrap().flush()
+    }
+}
+
+impl fmt::Debug for SharedWriter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SharedWriter")
+            .field("is_terminal", &self.is_terminal)
+            .finish()
+    }
+}
+
+impl PartialEq for SharedWriter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for SharedWriter {}
+
+/// Styles of the different elements of the line.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Styles {
+    body: Style,
+    extra: Style,
+    head: Style,
+    percent: Style,
+    prefix: Style,
+    void: Style,
+}
+
+/// A piece of text of the line, styled depending on the field it displays.
 #[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Segment {
+    /// The field displayed by this segment, or `None` for text written in
+    /// the template.
+    pub field: Option<Field>,
+
+    /// The displayed text, without escape sequences.
+    pub text: String,
+
+    /// The style which should be applied to the text.
+    pub style: Style,
+}
+
+/// Partially filled cells used to draw smooth progress bars.
+pub const SMOOTH_PARTIALS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Partially filled cells used to draw smooth progress bars with ASCII
+/// characters only.
+pub const SMOOTH_ASCII_PARTIALS: [char; 4] = ['.', ':', '-', '='];
+
+#[derive(Clone, Debug, PartialEq)]
 struct ProgressConfig {
     bar_position: BarPosition,
     bar_width: usize,
+    color_mode: ColorMode,
+    display_mode: DisplayMode,
     display_width: Option<usize>,
     extra_infos: String,
+    log_delay: Duration,
+    log_step: f32,
     output_stream: OutputStream,
     prefix: String,
     refresh_delay: Duration,
     shape_body: char,
     shape_head: char,
+    shape_partials: Vec<char>,
+    shape_spinner: Vec<char>,
     shape_void: char,
+    steady_tick: bool,
+    styles: Styles,
+    template: Option<Template>,
 }
 
 impl Default for ProgressConfig {
@@ -63,14 +250,88 @@ impl Default for ProgressConfig {
         Self {
             bar_position: BarPosition::Left,
             bar_width: 40,
+            color_mode: ColorMode::Auto,
+            display_mode: DisplayMode::Auto,
             display_width: None,
             extra_infos: String::new(),
+            log_delay: Duration::from_secs(10),
+            log_step: 10.,
             output_stream: OutputStream::StdOut,
             prefix: String::new(),
             refresh_delay: Duration::from_millis(200),
             shape_body: '=',
             shape_head: '>',
+            shape_partials: Vec::new(),
+            shape_spinner: vec!['-', '\\', '|', '/'],
             shape_void: ' ',
+            steady_tick: false,
+            styles: Styles::default(),
+            template: None,
+        }
+    }
+}
+
+//  ____  _        _
+// / ___|| |_ __ _| |_ ___
+// \___ \| __/ _` | __/ __|
+//  ___) | || (_| | |_\__ \
+// |____/ \__\__,_|\__|___/
+//
+
+/// Numeric informations about the progression, displayed by the fields of a
+/// template. They are usually supplied by a `StepProgress`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    /// Current step.
+    pub pos: usize,
+
+    /// Expected number of steps.
+    pub total: Option<usize>,
+
+    /// Time elapsed since the beginning.
+    pub elapsed: Duration,
+
+    /// Estimated remaining time, if it is still running.
+    pub eta: Option<Duration>,
+
+    /// Number of steps per second.
+    pub rate: f32,
+
+    /// Unit of steps.
+    pub unit: String,
+
+    /// Wether counts are converted to human-readable units.
+    pub humanize: bool,
+}
+
+impl Stats {
+    fn format_count(&self, count: usize) -> String {
+        if self.humanize {
+            let (count, unit_prefix) = convert_to_unit(count as f32);
+            format!("{:.2}{}{}", count, unit_prefix, self.unit)
+        } else {
+            format!("{}{}", count, self.unit)
+        }
+    }
+
+    fn field_text(&self, field: Field) -> String {
+        match field {
+            Field::Pos => self.format_count(self.pos),
+            Field::Total => self
+                .total
+                .map(|total| self.format_count(total))
+                .unwrap_or_default(),
+            Field::Elapsed => format!("{:.1?}", self.elapsed),
+            Field::Eta => self
+                .eta
+                .map(|eta| format!("{:.1?}", eta))
+                .unwrap_or_default(),
+            Field::Time => format!("{:.1?}", self.eta.unwrap_or(self.elapsed)),
+            Field::Rate => {
+                let (rate, unit_prefix) = convert_to_unit(self.rate);
+                format!("{:.1} {}{}/s", rate, unit_prefix, self.unit)
+            }
+            _ => String::new(),
         }
     }
 }
@@ -87,6 +348,10 @@ impl Default for ProgressConfig {
 /// # Example
 ///
 /// ```
+/// # use prog_rs::prelude::*;
+/// # use prog_rs::{OutputStream, Progress};
+/// # use std::thread::sleep;
+/// # use std::time::Duration;
 /// let mut progress = Progress::new()
 ///     .with_bar_width(30)
 ///     .with_extra_infos("Hello, World!")
@@ -104,19 +369,43 @@ impl Default for ProgressConfig {
 /// ```
 #[derive(Clone, Debug)]
 pub struct Progress {
+    /// Declared first so that the ticker thread is stopped before the rest
+    /// of the progress bar is dropped.
+    ticker: Ticker,
     config: ProgressConfig,
+    last_log: Option<(Instant, Option<f32>)>,
     last_update_time: Option<Instant>,
+    multi_slot: Option<Arc<MultiSlot>>,
+    owner: BlockOwner,
+    progress: Option<f32>,
+    stats: Option<Stats>,
+    tick: usize,
 }
 
-impl<'a> Progress {
+impl Progress {
     /// Create a new progress bar with default display settings.
     pub fn new() -> Self {
         Self {
+            ticker: Ticker::default(),
             config: ProgressConfig::default(),
+            last_log: None,
             last_update_time: None,
+            multi_slot: None,
+            owner: BlockOwner::default(),
+            progress: Some(0.),
+            stats: None,
+            tick: 0,
         }
     }
 
+    pub(crate) fn set_multi_slot(&mut self, multi_slot: Option<MultiSlot>) {
+        self.multi_slot = multi_slot.map(Arc::new);
+    }
+
+    pub(crate) fn multi_slot(&self) -> Option<&MultiSlot> {
+        self.multi_slot.as_deref()
+    }
+
     /// Update extra informations displayed next to the progress bar.
     pub fn set_extra_infos<S>(&mut self, extra_infos: S)
     where
@@ -125,6 +414,25 @@ impl<'a> Progress {
         self.config.extra_infos = extra_infos.into()
     }
 
+    /// Update the text displayed before the progress bar.
+    pub fn set_prefix<S>(&mut self, prefix: S)
+    where
+        S: Into<String>,
+    {
+        self.config.prefix = prefix.into()
+    }
+
+    /// Update the progression without redrawing, `None` means that it is
+    /// unknown.
+    pub(crate) fn set_progress(&mut self, progress: Option<f32>) {
+        self.progress = progress
+    }
+
+    /// Update numeric informations displayed next to the progress bar.
+    pub fn set_stats(&mut self, stats: Stats) {
+        self.stats = Some(stats)
+    }
+
     /// Check if the timer specified by `with_refresh_delay` has decayed.
     pub fn need_refresh(&self) -> bool {
         if let Some(last_update_time) = self.last_update_time {
@@ -133,95 +441,533 @@ impl<'a> Progress {
         true
     }
 
-    fn bar_shape(&self, progress: f32) -> (usize, usize, usize) {
-        let body_length = min(
-            self.config.bar_width + 1,
-            (progress * (self.config.bar_width + 1) as f32).round() as usize,
+    /// Compute the number of cells of the body, the character of the head
+    /// and the number of cells of the background of the bar.
+    fn bar_shape(&self, progress: f32, width: usize) -> (usize, Option<char>, usize) {
+        let partials = &self.config.shape_partials;
+
+        if partials.is_empty() {
+            let body_length = min(width, (progress * width as f32).round() as usize);
+            let void_length = width - body_length;
+
+            if void_length > 0 {
+                return (body_length, Some(self.config.shape_head), void_length - 1);
+            }
+
+            return (body_length, None, void_length);
+        }
+
+        // Each cell can be filled with `partials.len() + 1` different levels
+        let levels = partials.len() + 1;
+        let filled = min(
+            width * levels,
+            (progress * (width * levels) as f32).round() as usize,
         );
-        let mut void_length = (self.config.bar_width + 1) - body_length;
-        let mut head_length = 0;
+        let body_length = filled / levels;
 
-        if void_length > 0 {
-            void_length -= 1;
-            head_length += 1;
+        if body_length == width {
+            return (body_length, None, 0);
         }
 
-        (body_length, void_length, head_length)
+        let head = match filled % levels {
+            0 => self.config.shape_void,
+            level => partials[level - 1],
+        };
+
+        (body_length, Some(head), width - body_length - 1)
     }
 
     /// Redraw the progress bar if the timer has decayed.
     pub fn update(&mut self, progress: f32) -> io::Result<()> {
+        self.progress = Some(progress);
+
         if !self.need_refresh() {
             return Ok(());
         }
 
+        self.draw(false)
+    }
+
+    /// Redraw the progress bar with an animation if the timer has decayed,
+    /// this is used when the progression is unknown.
+    pub fn update_indeterminate(&mut self) -> io::Result<()> {
+        self.progress = None;
+
+        if !self.need_refresh() {
+            return Ok(());
+        }
+
+        self.draw(false)
+    }
+
+    /// Redraw the progress bar for the last time.
+    pub fn finished(&mut self) -> io::Result<()> {
+        self.progress = Some(1.0);
+        self.draw(true)
+    }
+
+    /// Print a line of text above the progress bar, in its output stream,
+    /// the progress bar is then drawn again below.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use prog_rs::Progress;
+    ///
+    /// let mut progress = Progress::new();
+    ///
+    /// for i in 0..1_000 {
+    ///     if i % 100 == 0 {
+    ///         progress.println(format!("Reached {}", i)).unwrap();
+    ///     }
+    ///
+    ///     progress.update(i as f32 / 1_000.).unwrap();
+    /// }
+    ///
+    /// progress.finished().unwrap();
+    /// ```
+    pub fn println<S>(&mut self, text: S) -> io::Result<()>
+    where
+        S: AsRef<str>,
+    {
+        screen::lock().println(&self.output_stream(), text.as_ref())
+    }
+
+    /// Hide the progress bar while running `f`, it is drawn again once `f`
+    /// returns. Other threads can't draw progress bars in the meantime.
+    pub fn suspend<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        screen::suspend(f)
+    }
+
+    /// Build the line representing the current state of the progress bar,
+    /// fitting in `width` columns, without displaying it.
+    ///
+    /// The output stream is not checked: with `ColorMode::Auto`, styles are
+    /// applied unless the `NO_COLOR` environment variable is set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use prog_rs::prelude::*;
+    /// use prog_rs::{ColorMode, Progress};
+    ///
+    /// let mut progress = Progress::new()
+    ///     .with_prefix("Loading")
+    ///     .with_bar_width(9)
+    ///     .with_color_mode(ColorMode::Never);
+    ///
+    /// progress.update(0.5).unwrap();
+    /// assert_eq!(progress.render(30), "Loading  50.0% [=====>    ]   ");
+    /// ```
+    pub fn render(&self, width: usize) -> String {
+        self.paint(
+            self.layout(width, true),
+            self.config.color_mode.enabled(true),
+        )
+    }
+
+    /// Split the line representing the current state of the progress bar,
+    /// fitting in `width` columns, into styled pieces of text.
+    pub fn segments(&self, width: usize) -> Vec<Segment> {
+        self.layout(width, true)
+    }
+
+    fn draw(&mut self, finished: bool) -> io::Result<()> {
+        if !self.config.steady_tick {
+            return self.display(finished);
+        }
+
+        // Draw while the thread is locked, it will then be synchronized
+        let mut ticker = mem::take(&mut self.ticker);
+        let state = ticker.state(self);
+        self.ticker = ticker;
+
+        let mut state = state.lock().unwrap();
+        self.tick = max(self.tick, state.progress.tick);
+        let result = self.display(finished);
+
+        // The copy draws the same block, which must not be released when the
+        // previous copy is dropped
+        let tick = self.tick;
+        let mut owner = mem::take(&mut state.progress.owner);
+        owner.share(&self.owner);
+        state.progress.clone_from(self);
+        state.progress.owner = owner;
+        state.progress.tick = tick;
+        state.last_draw = Instant::now();
+        state.synced_at = state.last_draw;
+        state.stopped = finished;
+        self.ticker.clear_step();
+        result
+    }
+
+    /// Send the current step to the thread redrawing the progress bar, if
+    /// there is one, without redrawing it.
+    pub(crate) fn sync_step(&self, step: usize) {
+        self.ticker.publish_step(step);
+    }
+
+    /// Move to given step without redrawing, the progression is updated if
+    /// the total is known.
+    pub(crate) fn set_pos(&mut self, pos: usize) {
+        if let Some(stats) = &mut self.stats {
+            stats.pos = pos;
+
+            if let (Some(total), Some(_)) = (stats.total, self.progress) {
+                self.progress = Some(pos as f32 / total as f32);
+            }
+        }
+    }
+
+    pub(crate) fn refresh_delay(&self) -> Duration {
+        self.config.refresh_delay
+    }
+
+    /// Redraw as if `elapsed` more time passed since stats were updated.
+    pub(crate) fn redraw_after(&mut self, elapsed: Duration) -> io::Result<()> {
+        let stats = self.stats.clone();
+
+        if let Some(stats) = &mut self.stats {
+            stats.elapsed += elapsed;
+            stats.eta = stats.eta.map(|eta| eta.saturating_sub(elapsed));
+        }
+
+        let result = self.display(false);
+        self.stats = stats;
+        result
+    }
+
+    fn display(&mut self, finished: bool) -> io::Result<()> {
+        if self.log_mode() {
+            return self.draw_log(finished);
+        }
+
         self.last_update_time = Some(Instant::now());
+        self.tick = self.tick.wrapping_add(1);
+        let line = self.line();
 
-        let (body, void, head) = self.bar_shape(progress);
-        let body = self.config.shape_body.to_string().repeat(body);
-        let head = self.config.shape_head.to_string().repeat(head);
-        let void = self.config.shape_void.to_string().repeat(void);
+        if let Some(multi_slot) = &self.multi_slot {
+            return multi_slot.draw(line, finished);
+        }
 
-        // Compute display shape
-        let required_width =
-            self.config.bar_width + self.config.prefix.len() + self.config.extra_infos.len() + 13;
+        screen::lock_for_draw().draw(&mut self.owner, &self.config.output_stream, &line, finished)
+    }
+
+    /// Print a complete line if enough time or progression passed since the
+    /// last one.
+    fn draw_log(&mut self, finished: bool) -> io::Result<()> {
+        let now = Instant::now();
+        let progress = self.progress;
+        self.last_update_time = Some(now);
+
+        let need_log = finished
+            || match self.last_log {
+                None => true,
+                Some((time, last_progress)) => {
+                    let step = self.config.log_step / 100.;
+                    let crossed_step = match (last_progress, progress) {
+                        (Some(last), Some(cur)) if step > 0. => {
+                            (cur / step).floor() > (last / step).floor()
+                        }
+                        _ => false,
+                    };
+
+                    crossed_step || now - time >= self.config.log_delay
+                }
+            };
+
+        if !need_log {
+            return Ok(());
+        }
+
+        self.last_log = Some((now, progress));
+        self.tick = self.tick.wrapping_add(1);
+        let line = self.line();
+        writeln!(&mut self.output_stream().get(), "{}", line.trim_end())
+    }
+
+    /// Check if complete lines should be printed instead of redrawing the
+    /// progress bar in place.
+    fn log_mode(&self) -> bool {
+        match self.config.display_mode {
+            DisplayMode::Auto => !self.output_stream().is_terminal(),
+            DisplayMode::Terminal => false,
+            DisplayMode::Log => true,
+        }
+    }
+
+    /// Build the text of the line representing the progress bar in the
+    /// display.
+    fn line(&self) -> String {
         let display_width = self
             .config
             .display_width
-            .unwrap_or_else(|| term_size::dimensions_stdout().map(|(w, _)| w).unwrap_or(80));
-
-        let (prefix, padding) = {
-            if display_width >= required_width {
-                (
-                    &self.config.prefix[..],
-                    " ".repeat(display_width - required_width),
-                )
-            } else if self.config.prefix.len() >= required_width - display_width {
-                let prefix_len = self.config.prefix.len() - (required_width - display_width);
-                (&self.config.prefix[0..prefix_len], String::new())
-            } else {
-                ("", String::new())
+            .unwrap_or_else(|| term_size::dimensions_stdout().map(|(w, _)| w).unwrap_or(80))
+            .saturating_sub(1);
+
+        let colored = self
+            .config
+            .color_mode
+            .enabled(self.output_stream().is_terminal());
+
+        // Blank spaces are useless when the line is not redrawn
+        self.paint(self.layout(display_width, !self.log_mode()), colored)
+    }
+
+    /// Join segments into a text, with styles if `colored` is set.
+    fn paint(&self, segments: Vec<Segment>, colored: bool) -> String {
+        segments
+            .into_iter()
+            .map(|segment| {
+                if colored {
+                    segment.style.paint(&segment.text)
+                } else {
+                    segment.text
+                }
+            })
+            .collect()
+    }
+
+    /// Split the line representing the progress bar into styled pieces of
+    /// text fitting in `width` columns.
+    fn layout(&self, width: usize, fill: bool) -> Vec<Segment> {
+        let progress = self.progress;
+        let template = match &self.config.template {
+            Some(template) => template,
+            None => self.default_template(progress.is_some()),
+        };
+
+        // Render all fields, the prefix and blank spaces will then be resized
+        // to fit the display
+        let mut segments = Vec::new();
+
+        for chunk in template.chunks() {
+            match chunk {
+                Chunk::Text(text) => segments.push(Segment {
+                    field: None,
+                    text: text.clone(),
+                    style: Style::new(),
+                }),
+                Chunk::Field {
+                    field: Field::Bar,
+                    width,
+                    ..
+                } => segments.extend(self.bar_segments(progress, *width)),
+                Chunk::Field {
+                    field,
+                    width,
+                    alignment,
+                } => segments.push(Segment {
+                    field: Some(*field),
+                    text: alignment.pad(&self.field_text(*field, progress), width.unwrap_or(0)),
+                    style: self.field_style(*field),
+                }),
             }
+        }
+
+        // Compute display shape
+        let required_width: usize = segments
+            .iter()
+            .filter(|segment| segment.field != Some(Field::Fill))
+            .map(|segment| text_width(&segment.text))
+            .sum();
+
+        if width >= required_width {
+            let fills = segments
+                .iter()
+                .filter(|segment| segment.field == Some(Field::Fill))
+                .count();
+            let padding = if fill { width - required_width } else { 0 };
+
+            for (i, segment) in segments
+                .iter_mut()
+                .filter(|segment| segment.field == Some(Field::Fill))
+                .enumerate()
+            {
+                let extra = if i < padding % fills { 1 } else { 0 };
+                segment.text = " ".repeat(padding / fills + extra);
+            }
+        } else {
+            let mut excess = required_width - width;
+
+            for segment in segments
+                .iter_mut()
+                .filter(|segment| segment.field == Some(Field::Prefix))
+            {
+                let width = text_width(&segment.text);
+                let cut = min(excess, width);
+                segment.text = truncate(&segment.text, width - cut);
+                excess -= cut;
+            }
+        }
+
+        segments
+    }
+
+    /// Get the layout used when no template is specified, each variant is
+    /// only parsed once.
+    fn default_template(&self, known_progress: bool) -> &'static Template {
+        static TEMPLATES: [OnceLock<Template>; 8] = [
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+            OnceLock::new(),
+        ];
+
+        let with_stats = self.stats.is_some();
+        let bar_position = self.config.bar_position;
+        let index = 4 * bar_position as usize + 2 * with_stats as usize + known_progress as usize;
+
+        TEMPLATES[index].get_or_init(|| {
+            let stats = match (with_stats, known_progress) {
+                (false, _) => "",
+                (true, true) => "{pos}/{total}, {time} ({rate}) ",
+                (true, false) => "{pos}, {elapsed} ({rate}) ",
+            };
+
+            let progress = if known_progress {
+                "{percent:>6}"
+            } else {
+                "{spinner}"
+            };
+
+            let template = match bar_position {
+                BarPosition::Left => format!(
+                    "{{prefix}} {} [{{bar}}] {}{{extra}}{{fill}}",
+                    progress, stats
+                ),
+                BarPosition::Right => format!(
+                    "{{prefix}} {{fill}}{}{{extra}} [{{bar}}] {}",
+                    stats, progress
+                ),
+            };
+
+            Template::parse(&template).expect("invalid default template")
+        })
+    }
+
+    fn bar_segments(&self, progress: Option<f32>, width: Option<usize>) -> Vec<Segment> {
+        let width = width.unwrap_or(self.config.bar_width + 1);
+
+        let progress = match progress {
+            Some(progress) => progress,
+            None => return self.bouncing_bar_segments(width),
         };
 
-        let text = match self.config.bar_position {
-            BarPosition::Left => format!(
-                "\r{} {:>5.1}% [{}{}{}] {}{}",
-                prefix,
-                100. * progress,
-                body,
-                head,
-                void,
-                self.config.extra_infos,
-                padding
-            ),
-            BarPosition::Right => format!(
-                "\r{} {}{} [{}{}{}] {:>5.1}%",
-                prefix,
-                padding,
-                self.config.extra_infos,
-                body,
-                head,
-                void,
-                100. * progress
-            ),
+        let (body, head, void) = self.bar_shape(progress, width);
+
+        // Partially filled cells are a continuation of the body
+        let head_style = {
+            if self.config.shape_partials.is_empty() {
+                self.config.styles.head
+            } else {
+                self.config.styles.body
+            }
         };
 
-        // Display text
-        let mut stream = self.config.output_stream.get();
-        stream.write_all(&text.as_bytes())?;
-        stream.flush()
+        vec![
+            Segment {
+                field: Some(Field::Bar),
+                text: self.config.shape_body.to_string().repeat(body),
+                style: self.config.styles.body,
+            },
+            Segment {
+                field: Some(Field::Bar),
+                text: head.map(String::from).unwrap_or_default(),
+                style: head_style,
+            },
+            Segment {
+                field: Some(Field::Bar),
+                text: self.config.shape_void.to_string().repeat(void),
+                style: self.config.styles.void,
+            },
+        ]
     }
 
-    /// Redraw the progress bar for the last time.
-    pub fn finished(&mut self) -> io::Result<()> {
-        self.last_update_time = None;
-        self.update(1.0)?;
-        writeln!(&mut self.config.output_stream.get())
+    /// Draw a block bouncing from one side of the bar to the other.
+    fn bouncing_bar_segments(&self, width: usize) -> Vec<Segment> {
+        let block_length = min(width, max(1, width / 5));
+        let period = max(1, 2 * (width - block_length));
+        let mut position = self.tick % period;
+
+        if position > width - block_length {
+            position = period - position;
+        }
+
+        vec![
+            Segment {
+                field: Some(Field::Bar),
+                text: self.config.shape_void.to_string().repeat(position),
+                style: self.config.styles.void,
+            },
+            Segment {
+                field: Some(Field::Bar),
+                text: self.config.shape_body.to_string().repeat(block_length),
+                style: self.config.styles.body,
+            },
+            Segment {
+                field: Some(Field::Bar),
+                text: self
+                    .config
+                    .shape_void
+                    .to_string()
+                    .repeat(width - block_length - position),
+                style: self.config.styles.void,
+            },
+        ]
+    }
+
+    fn field_text(&self, field: Field, progress: Option<f32>) -> String {
+        match field {
+            Field::Prefix => self.config.prefix.clone(),
+            Field::Percent => progress
+                .map(|progress| format!("{:.1}%", 100. * progress))
+                .unwrap_or_default(),
+            Field::Spinner => {
+                let spinner = &self.config.shape_spinner;
+
+                if spinner.is_empty() {
+                    String::new()
+                } else {
+                    spinner[self.tick % spinner.len()].to_string()
+                }
+            }
+            Field::Extra => self.config.extra_infos.clone(),
+            Field::Bar | Field::Fill => String::new(),
+            _ => self
+                .stats
+                .as_ref()
+                .map(|stats| stats.field_text(field))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn field_style(&self, field: Field) -> Style {
+        match field {
+            Field::Prefix => self.config.styles.prefix,
+            Field::Percent | Field::Spinner => self.config.styles.percent,
+            Field::Bar | Field::Fill => Style::new(),
+            _ => self.config.styles.extra,
+        }
+    }
+
+    /// Get the stream this progress bar is actually displayed in.
+    fn output_stream(&self) -> OutputStream {
+        match &self.multi_slot {
+            Some(multi_slot) => multi_slot.output_stream(),
+            None => self.config.output_stream.clone(),
+        }
     }
 }
 
-impl<'a> Default for Progress {
+impl Default for Progress {
     fn default() -> Self {
         Self::new()
     }
@@ -238,6 +984,22 @@ impl<'a> Default for Progress {
 pub trait WithProgress: Sized {
     fn get_progress(&mut self) -> &mut Progress;
 
+    /// Print a line of text above the progress bar, see `Progress::println`.
+    fn println<S>(&mut self, text: S) -> io::Result<()>
+    where
+        S: AsRef<str>,
+    {
+        self.get_progress().println(text)
+    }
+
+    /// Hide the progress bar while running `f`, see `Progress::suspend`.
+    fn suspend<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        self.get_progress().suspend(f)
+    }
+
     /// Specify a progress bar to use, which allows to copy configuration.
     ///
     /// # Example
@@ -245,6 +1007,7 @@ pub trait WithProgress: Sized {
     /// ```
     /// use prog_rs::prelude::*;
     /// use prog_rs::{OutputStream, Progress};
+    /// # fn do_something(_: i32) {}
     ///
     /// let progress = Progress::new()
     ///     .with_bar_width(50)
@@ -275,6 +1038,21 @@ pub trait WithProgress: Sized {
         self
     }
 
+    /// Change when colors and text styles are enabled. By default, they are
+    /// only enabled when displaying in a terminal and the `NO_COLOR`
+    /// environment variable is not set.
+    fn with_color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.get_progress().config.color_mode = color_mode;
+        self
+    }
+
+    /// Change the way the progress is displayed. By default, complete lines
+    /// are periodically printed when the output stream is not a terminal.
+    fn with_display_mode(mut self, display_mode: DisplayMode) -> Self {
+        self.get_progress().config.display_mode = display_mode;
+        self
+    }
+
     /// Change the width of the text the displayed informations should try to
     /// fit in. The terminal width will be detected by default.
     fn with_display_width(mut self, display_width: usize) -> Self {
@@ -291,6 +1069,21 @@ pub trait WithProgress: Sized {
         self
     }
 
+    /// Change the maximal delay between two lines printed in `Log` display
+    /// mode. By default, a line is printed every 10 seconds.
+    fn with_log_delay(mut self, log_delay: Duration) -> Self {
+        self.get_progress().config.log_delay = log_delay;
+        self
+    }
+
+    /// Change the percentage of progression after which a new line is
+    /// printed in `Log` display mode. By default, a line is printed every
+    /// 10%.
+    fn with_log_step(mut self, log_step: f32) -> Self {
+        self.get_progress().config.log_step = log_step;
+        self
+    }
+
     /// Change the output stream the progress bar is displayed in. By default
     /// standart output is used.
     fn with_output_stream(mut self, output_stream: OutputStream) -> Self {
@@ -304,6 +1097,7 @@ pub trait WithProgress: Sized {
     ///
     /// ```
     /// use prog_rs::prelude::*;
+    /// # fn do_something(_: i32) {}
     ///
     /// for i in (0..1000)
     ///     .progress()
@@ -311,6 +1105,7 @@ pub trait WithProgress: Sized {
     /// {
     ///     do_something(i);
     /// }
+    /// ```
     fn with_prefix<S>(mut self, prefix: S) -> Self
     where
         S: Into<String>,
@@ -319,12 +1114,50 @@ pub trait WithProgress: Sized {
         self
     }
 
+    /// Change the layout of the line displaying the progress bar, see
+    /// `Template` for more details. By default, the layout is chosen
+    /// depending on the bar position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use prog_rs::prelude::*;
+    ///
+    /// for _ in (0..1000)
+    ///     .progress()
+    ///     .with_template("{prefix} {bar:20} {pos}/{total} {eta}".parse().unwrap())
+    /// {}
+    /// ```
+    fn with_template(mut self, template: Template) -> Self {
+        self.get_progress().config.template = Some(template);
+        self
+    }
+
     /// Change the minimum delay between two display updates.
     fn with_refresh_delay(mut self, refresh_delay: Duration) -> Self {
         self.get_progress().config.refresh_delay = refresh_delay;
         self
     }
 
+    /// Redraw the progress bar from a background thread when it is not
+    /// updated for longer than the refresh delay, so that the elapsed time
+    /// and animations keep moving during slow steps. The thread is stopped
+    /// once the progress bar is finished or dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use prog_rs::prelude::*;
+    ///
+    /// for _ in (0..3).progress().with_steady_tick(true) {
+    ///     std::thread::sleep(std::time::Duration::from_millis(500));
+    /// }
+    /// ```
+    fn with_steady_tick(mut self, steady_tick: bool) -> Self {
+        self.get_progress().config.steady_tick = steady_tick;
+        self
+    }
+
     /// Change the character used to draw the body of the progress bar.
     fn with_shape_body(mut self, shape_body: char) -> Self {
         self.get_progress().config.shape_body = shape_body;
@@ -342,6 +1175,89 @@ pub trait WithProgress: Sized {
         self.get_progress().config.shape_void = shape_void;
         self
     }
+
+    /// Change the characters used to draw a partially filled cell at the end
+    /// of the body, from the least to the most filled. When some are given,
+    /// they replace the head of the progress bar.
+    fn with_shape_partials<I>(mut self, shape_partials: I) -> Self
+    where
+        I: IntoIterator<Item = char>,
+    {
+        self.get_progress().config.shape_partials = shape_partials.into_iter().collect();
+        self
+    }
+
+    /// Change the characters successively displayed by the spinner, which
+    /// is displayed instead of the percentage when the progression is
+    /// unknown.
+    fn with_shape_spinner<I>(mut self, shape_spinner: I) -> Self
+    where
+        I: IntoIterator<Item = char>,
+    {
+        self.get_progress().config.shape_spinner = shape_spinner.into_iter().collect();
+        self
+    }
+
+    /// Draw a smooth progress bar using unicode blocks, which gives a
+    /// resolution of an eighth of a cell.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use prog_rs::prelude::*;
+    ///
+    /// for _ in (0..1000).progress().with_shape_smooth() {}
+    /// ```
+    fn with_shape_smooth(self) -> Self {
+        self.with_shape_body('█')
+            .with_shape_partials(SMOOTH_PARTIALS.iter().copied())
+            .with_shape_void(' ')
+    }
+
+    /// Draw a smooth progress bar using ASCII characters only, for displays
+    /// which don't support unicode blocks.
+    fn with_shape_smooth_ascii(self) -> Self {
+        self.with_shape_body('#')
+            .with_shape_partials(SMOOTH_ASCII_PARTIALS.iter().copied())
+            .with_shape_void(' ')
+    }
+
+    /// Change the style of the text displayed in front of progress
+    /// informations.
+    fn with_style_prefix(mut self, style: Style) -> Self {
+        self.get_progress().config.styles.prefix = style;
+        self
+    }
+
+    /// Change the style of the body of the progress bar.
+    fn with_style_body(mut self, style: Style) -> Self {
+        self.get_progress().config.styles.body = style;
+        self
+    }
+
+    /// Change the style of the head of the progress bar.
+    fn with_style_head(mut self, style: Style) -> Self {
+        self.get_progress().config.styles.head = style;
+        self
+    }
+
+    /// Change the style of the background of the progress bar.
+    fn with_style_void(mut self, style: Style) -> Self {
+        self.get_progress().config.styles.void = style;
+        self
+    }
+
+    /// Change the style of the percentage of progression.
+    fn with_style_percent(mut self, style: Style) -> Self {
+        self.get_progress().config.styles.percent = style;
+        self
+    }
+
+    /// Change the style of extra informations and numeric informations.
+    fn with_style_extra(mut self, style: Style) -> Self {
+        self.get_progress().config.styles.extra = style;
+        self
+    }
 }
 
 impl WithProgress for Progress {
@@ -349,3 +1265,132 @@ impl WithProgress for Progress {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::buffer_output;
+
+    #[test]
+    fn render_ignores_output_stream() {
+        let progress = Progress::new()
+            .with_template("{prefix}".parse().unwrap())
+            .with_prefix("Loading")
+            .with_style_prefix(Style::new().bold());
+
+        let styled = Style::new().bold().paint("Loading");
+        assert_eq!(
+            progress
+                .clone()
+                .with_color_mode(ColorMode::Always)
+                .render(80),
+            styled
+        );
+        assert_eq!(
+            progress.with_color_mode(ColorMode::Never).render(80),
+            "Loading"
+        );
+    }
+
+    /// Update a progress bar printing lines in a buffer through given
+    /// progressions, and get printed lines.
+    fn log_lines(progress: Progress, updates: &[f32]) -> Vec<String> {
+        let (output_stream, output) = buffer_output();
+
+        let mut progress = progress
+            .with_template("{prefix} {percent}".parse().unwrap())
+            .with_prefix("Loading")
+            .with_display_mode(DisplayMode::Log)
+            .with_refresh_delay(Duration::ZERO)
+            .with_output_stream(output_stream);
+
+        for &update in updates {
+            progress.update(update).unwrap();
+        }
+
+        progress.finished().unwrap();
+        output().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn log_lines_at_each_step() {
+        let progress = Progress::new()
+            .with_log_step(25.)
+            .with_log_delay(Duration::from_secs(3600));
+
+        assert_eq!(
+            log_lines(progress, &[0.1, 0.2, 0.3, 0.4, 0.6, 0.7]),
+            [
+                "Loading 10.0%",
+                "Loading 30.0%",
+                "Loading 60.0%",
+                "Loading 100.0%"
+            ]
+        );
+    }
+
+    #[test]
+    fn log_lines_after_delay() {
+        let progress = Progress::new().with_log_step(0.);
+
+        // Only the first and the final line are printed before the delay
+        let lines = log_lines(
+            progress.clone().with_log_delay(Duration::from_secs(3600)),
+            &[0.1, 0.2, 0.3],
+        );
+        assert_eq!(lines, ["Loading 10.0%", "Loading 100.0%"]);
+
+        let lines = log_lines(progress.with_log_delay(Duration::ZERO), &[0.1, 0.2, 0.3]);
+        assert_eq!(
+            lines,
+            [
+                "Loading 10.0%",
+                "Loading 20.0%",
+                "Loading 30.0%",
+                "Loading 100.0%"
+            ]
+        );
+    }
+
+    #[test]
+    fn smooth_partials() {
+        let render = |progress: Progress, fraction| {
+            let mut progress = progress
+                .with_template("[{bar:5}]".parse().unwrap())
+                .with_color_mode(ColorMode::Never);
+
+            progress.set_progress(Some(fraction));
+            progress.render(80)
+        };
+
+        let smooth = Progress::new().with_shape_smooth();
+        assert_eq!(render(smooth.clone(), 0.), "[     ]");
+        assert_eq!(render(smooth.clone(), 0.5), "[██▌  ]");
+        assert_eq!(render(smooth, 1.), "[█████]");
+
+        let ascii = Progress::new().with_shape_smooth_ascii();
+        assert_eq!(render(ascii.clone(), 0.), "[     ]");
+        assert_eq!(render(ascii.clone(), 0.5), "[##-  ]");
+        assert_eq!(render(ascii, 1.), "[#####]");
+    }
+
+    #[test]
+    fn bouncing_bar() {
+        let mut progress = Progress::new()
+            .with_template("{spinner} [{bar:10}]".parse().unwrap())
+            .with_color_mode(ColorMode::Never);
+
+        progress.set_progress(None);
+        let mut render = |tick| {
+            progress.tick = tick;
+            progress.render(80)
+        };
+
+        // The block goes back once it reaches the end of the bar
+        assert_eq!(render(0), "- [==        ]");
+        assert_eq!(render(3), "/ [   ==     ]");
+        assert_eq!(render(8), "- [        ==]");
+        assert_eq!(render(9), "\\ [       == ]");
+        assert_eq!(render(16), "- [==        ]");
+    }
+}
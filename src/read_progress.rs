@@ -0,0 +1,260 @@
+//! Defines a wrapper around readers to display a progress bar.
+
+use crate::step_progress::{StepProgress, WithStepProgress};
+
+use std::convert::TryInto;
+use std::io;
+
+/// A wrapper read only stream arround any reader, which counts read bytes.
+///
+/// If the reader implements `Seek`, the position in the stream is displayed,
+/// which can move backward. See `with_processed_bytes` to count all read
+/// bytes instead.
+///
+/// If the reader implements `BufRead`, so does the wrapper: bytes are then
+/// counted when they are consumed.
+///
+/// The total size is unknown by default, it can be specified using
+/// `with_max_step`.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::prelude::*;
+/// use prog_rs::ReadProgress;
+/// # use std::io::{Cursor, Read};
+///
+/// let data = vec![0; 1_000_000];
+/// let mut reader = ReadProgress::new(Cursor::new(data))
+///     .with_max_step(1_000_000)
+///     .with_prefix("Read data ...");
+///
+/// let mut buf = Vec::new();
+/// reader.read_to_end(&mut buf).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ReadProgress<R> {
+    inner: R,
+    processed_bytes: bool,
+    step_progress: StepProgress,
+}
+
+impl<R> ReadProgress<R>
+where
+    R: io::Read,
+{
+    /// Wrap a reader, the total size is unknown. For files, use
+    /// `FileProgress::new` to read it from their metadata.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            processed_bytes: false,
+            step_progress: StepProgress::new().with_humanize(true).with_unit("B"),
+        }
+    }
+}
+
+impl<R> ReadProgress<R> {
+    /// Count all processed bytes instead of tracking the position in the
+    /// stream: bytes read again after a backward seek are counted twice and
+    /// bytes skipped by a forward seek are not counted.
+    pub fn with_processed_bytes(mut self, processed_bytes: bool) -> Self {
+        self.processed_bytes = processed_bytes;
+        self
+    }
+
+    /// Get a reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Unwrap the reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> ReadProgress<R>
+where
+    R: io::Read,
+{
+    /// Count the result of a read, an empty read means the end of the input
+    /// unless the buffer was empty.
+    fn after_read(&mut self, step: usize, empty_buf: bool) {
+        if step > 0 {
+            self.step_progress.step(step);
+        } else if !empty_buf {
+            self.step_progress.finish();
+        }
+    }
+}
+
+impl<R> io::Seek for ReadProgress<R>
+where
+    R: io::Read + io::Seek,
+{
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let res = self.inner.seek(pos);
+
+        if let Ok(new_pos) = res {
+            if !self.processed_bytes {
+                self.step_progress
+                    .set_step(new_pos.try_into().expect("file size doesn't fit in usize"));
+            }
+        }
+
+        res
+    }
+}
+
+impl<R> io::Read for ReadProgress<R>
+where
+    R: io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let res = self.inner.read(buf);
+
+        if let Ok(step) = res {
+            self.after_read(step, buf.is_empty());
+        }
+
+        res
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        let res = self.inner.read_vectored(bufs);
+
+        if let Ok(step) = res {
+            self.after_read(step, bufs.iter().all(|buf| buf.is_empty()));
+        }
+
+        res
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let res = self.inner.read_to_end(buf);
+
+        if let Ok(step) = res {
+            self.step_progress.step(step);
+            self.step_progress.finish();
+        }
+
+        res
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        let res = self.inner.read_to_string(buf);
+
+        if let Ok(step) = res {
+            self.step_progress.step(step);
+            self.step_progress.finish();
+        }
+
+        res
+    }
+
+    // `read_exact` is not forwarded to the reader, the default implementation
+    // relies on `read` to count the bytes read before reaching the end of
+    // the input.
+}
+
+impl<R> io::BufRead for ReadProgress<R>
+where
+    R: io::BufRead,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let buf = self.inner.fill_buf()?;
+
+        if buf.is_empty() {
+            self.step_progress.finish();
+        }
+
+        Ok(buf)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.step_progress.step(amt);
+    }
+}
+
+impl<R> WithStepProgress for ReadProgress<R> {
+    fn get_step_progress(&mut self) -> &mut StepProgress {
+        &mut self.step_progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::prelude::*;
+    use std::io::{Cursor, SeekFrom};
+
+    use super::*;
+    use crate::progress::WithProgress;
+    use crate::style::ColorMode;
+    use crate::utils::sink_output;
+
+    #[test]
+    fn read_again_after_seek() {
+        let mut reader = ReadProgress::new(Cursor::new(vec![0; 100]))
+            .with_max_step(100)
+            .with_color_mode(ColorMode::Never)
+            .with_output_stream(sink_output());
+
+        // Read until the end, then read again from the beginning
+        reader.read_to_end(&mut Vec::new()).unwrap();
+        assert_eq!(reader.cur_step(), 100);
+
+        reader.seek(SeekFrom::Start(10)).unwrap();
+        reader.read_exact(&mut [0; 10]).unwrap();
+        assert_eq!(reader.cur_step(), 20);
+        assert!(reader.step_progress.render(80).contains(" 20.0%"));
+
+        reader.read_to_end(&mut Vec::new()).unwrap();
+        assert_eq!(reader.cur_step(), 100);
+        assert!(reader.step_progress.render(80).contains("100.0%"));
+    }
+
+    #[test]
+    fn count_processed_bytes() {
+        let mut reader = ReadProgress::new(Cursor::new(vec![0; 100]))
+            .with_processed_bytes(true)
+            .with_output_stream(sink_output());
+
+        reader.read_exact(&mut [0; 50]).unwrap();
+        reader.seek(SeekFrom::Start(10)).unwrap();
+        reader.read_exact(&mut [0; 20]).unwrap();
+        assert_eq!(reader.cur_step(), 70);
+    }
+
+    #[test]
+    fn count_bytes_before_end_of_input() {
+        let mut reader = ReadProgress::new(Cursor::new(vec![0; 10]))
+            .with_template("{pos}/{total}".parse().unwrap())
+            .with_humanize(false)
+            .with_output_stream(sink_output());
+
+        // The total is only known once the end of the input is reached
+        let err = reader.read_exact(&mut [0; 30]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(reader.cur_step(), 10);
+        assert_eq!(reader.step_progress.render(80), "10B/10B");
+    }
+
+    #[test]
+    fn count_consumed_lines() {
+        let data = "first\nsecond\nthird\n";
+
+        let mut reader = ReadProgress::new(Cursor::new(data))
+            .with_template("{pos}/{total}".parse().unwrap())
+            .with_humanize(false)
+            .with_output_stream(sink_output());
+
+        reader.read_line(&mut String::new()).unwrap();
+        assert_eq!(reader.step_progress.render(80), "6B/");
+
+        assert_eq!(reader.by_ref().lines().count(), 2);
+        assert_eq!(reader.cur_step(), data.len());
+        assert_eq!(reader.step_progress.render(80), "19B/19B");
+    }
+}
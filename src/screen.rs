@@ -0,0 +1,484 @@
+//! Keeps track of the progress bars currently drawn in the terminal, so that
+//! text can be printed above them.
+
+use std::io;
+use std::io::prelude::*;
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, ThreadId};
+
+use crate::progress::OutputStream;
+
+static SCREEN: Mutex<Screen> = Mutex::new(Screen {
+    block: None,
+    deferred: Vec::new(),
+    hidden: None,
+    suspended_by: None,
+});
+
+/// Notified when the screen is not suspended anymore.
+static RESUMED: Condvar = Condvar::new();
+
+/// Identifier of the next owner of a block.
+static NEXT_OWNER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Get exclusive access to the screen, wait if it is suspended by another
+/// thread.
+pub(crate) fn lock() -> MutexGuard<'static, Screen> {
+    let current = thread::current().id();
+    let screen = SCREEN.lock().unwrap_or_else(PoisonError::into_inner);
+
+    RESUMED
+        .wait_while(screen, |screen| {
+            screen.suspended_by.is_some_and(|thread| thread != current)
+        })
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Get exclusive access to the screen without waiting for it to be resumed,
+/// progress bars drawn while another thread suspends the screen are only
+/// displayed once it is resumed.
+///
+/// Draws may happen while other locks are held, such as the lock of a
+/// `MultiProgress`, which the suspending thread may need.
+pub(crate) fn lock_for_draw() -> MutexGuard<'static, Screen> {
+    SCREEN.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Print a line of text above the progress bars currently displayed, in
+/// the same output stream, or in the standard output if there is none.
+///
+/// This is useful when the progress bar is not reachable, for example when
+/// it is owned by a `for` loop:
+///
+/// ```
+/// use prog_rs::prelude::*;
+///
+/// for i in (0..1_000).progress() {
+///     if i % 100 == 0 {
+///         prog_rs::println(format!("Reached {}", i)).unwrap();
+///     }
+/// }
+/// ```
+pub fn println<S>(text: S) -> io::Result<()>
+where
+    S: AsRef<str>,
+{
+    let mut screen = lock();
+
+    let output_stream = match &screen.block {
+        Some(block) => block.output_stream.clone(),
+        None => OutputStream::StdOut,
+    };
+
+    screen.println(&output_stream, text.as_ref())
+}
+
+/// Hide the progress bars currently displayed while running `f`, they are
+/// drawn again once `f` returns. Other threads can't draw progress bars in
+/// the meantime.
+pub fn suspend<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let _suspended = {
+        let mut screen = lock();
+        screen.erase().ok();
+        let block = screen.block.take();
+
+        Suspended {
+            hidden: mem::replace(&mut screen.hidden, block),
+            previous: screen.suspended_by.replace(thread::current().id()),
+        }
+    };
+
+    f()
+}
+
+/// Identifies what drew a block, which is released when its owner is
+/// dropped without being finished.
+#[derive(Debug)]
+pub(crate) struct BlockOwner {
+    id: usize,
+    drew: bool,
+}
+
+impl BlockOwner {
+    /// Check if this owner ever drew a block.
+    pub(crate) fn drew(&self) -> bool {
+        self.drew
+    }
+
+    /// Draw the same block as `other`, which is then released when either
+    /// is dropped.
+    pub(crate) fn share(&mut self, other: &BlockOwner) {
+        self.id = other.id;
+    }
+}
+
+impl Default for BlockOwner {
+    fn default() -> Self {
+        Self {
+            id: NEXT_OWNER_ID.fetch_add(1, Ordering::Relaxed),
+            drew: false,
+        }
+    }
+}
+
+impl Clone for BlockOwner {
+    /// A clone of a progress bar draws its own block.
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Drop for BlockOwner {
+    fn drop(&mut self) {
+        if self.drew {
+            lock_for_draw().release(self.id).ok();
+        }
+    }
+}
+
+/// The text displayed by progress bars, which is erased before printing
+/// anything else.
+#[derive(Debug)]
+struct Block {
+    output_stream: OutputStream,
+    owner: usize,
+    text: String,
+}
+
+impl Block {
+    /// Escape sequence moving back to the first line of the block and
+    /// clearing it.
+    fn erase_sequence(&self) -> String {
+        match self.text.matches('\n').count() {
+            0 => "\r\x1b[J".to_string(),
+            lines => format!("\x1b[{}A\r\x1b[J", lines),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Screen {
+    block: Option<Block>,
+
+    /// Finished lines drawn by other threads during the suspension, which
+    /// are printed once it ends.
+    deferred: Vec<(OutputStream, String)>,
+
+    /// Block hidden during the suspension, which is drawn again once it
+    /// ends.
+    hidden: Option<Block>,
+
+    suspended_by: Option<ThreadId>,
+}
+
+impl Screen {
+    /// Replace currently displayed progress bars with `text`, which is kept
+    /// on screen until next draw, unless `finished` is set.
+    pub(crate) fn draw(
+        &mut self,
+        owner: &mut BlockOwner,
+        output_stream: &OutputStream,
+        text: &str,
+        finished: bool,
+    ) -> io::Result<()> {
+        owner.drew = true;
+
+        if self.suspended_by_other() {
+            if finished {
+                self.deferred
+                    .push((output_stream.clone(), text.to_string()));
+
+                if self.hidden_owner() == Some(owner.id) {
+                    self.hidden = None;
+                }
+            } else {
+                self.hidden = (!text.is_empty()).then(|| Block {
+                    output_stream: output_stream.clone(),
+                    owner: owner.id,
+                    text: text.to_string(),
+                });
+            }
+
+            return Ok(());
+        }
+
+        let mut buffer = match &self.block {
+            Some(block) if &block.output_stream == output_stream => block.erase_sequence(),
+            _ => {
+                self.erase()?;
+                "\r".to_string()
+            }
+        };
+
+        buffer.push_str(text);
+
+        if finished {
+            buffer.push('\n');
+        }
+
+        let mut stream = output_stream.get();
+        stream.write_all(buffer.as_bytes())?;
+        stream.flush()?;
+
+        self.block = {
+            if finished || text.is_empty() {
+                None
+            } else {
+                Some(Block {
+                    output_stream: output_stream.clone(),
+                    owner: owner.id,
+                    text: text.to_string(),
+                })
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Print a line of text above currently displayed progress bars.
+    pub(crate) fn println(&mut self, output_stream: &OutputStream, text: &str) -> io::Result<()> {
+        // Progress bars are redrawn with the same write if they are
+        // displayed in the same stream
+        let block = self
+            .block
+            .as_ref()
+            .filter(|block| &block.output_stream == output_stream);
+
+        let mut buffer = match block {
+            Some(block) => block.erase_sequence(),
+            None => {
+                self.erase()?;
+                String::new()
+            }
+        };
+
+        buffer.push_str(text);
+        buffer.push('\n');
+
+        if let Some(block) = block {
+            buffer.push_str(&block.text);
+        }
+
+        let mut stream = output_stream.get();
+        stream.write_all(buffer.as_bytes())?;
+        stream.flush()?;
+
+        if block.is_none() {
+            self.restore()?;
+        }
+
+        Ok(())
+    }
+
+    /// Leave the block drawn by `owner` on screen as it is, the next block
+    /// is then drawn below.
+    fn release(&mut self, owner: usize) -> io::Result<()> {
+        if self.suspended_by_other() {
+            if self.hidden_owner() == Some(owner) {
+                let block = self.hidden.take().unwrap();
+                self.deferred.push((block.output_stream, block.text));
+            }
+
+            return Ok(());
+        }
+
+        match self.block.take() {
+            Some(block) if block.owner == owner => {
+                let mut stream = block.output_stream.get();
+                stream.write_all(b"\n")?;
+                stream.flush()
+            }
+            block => {
+                self.block = block;
+                Ok(())
+            }
+        }
+    }
+
+    /// Check if the screen is suspended by another thread than the current
+    /// one.
+    fn suspended_by_other(&self) -> bool {
+        let current = thread::current().id();
+        matches!(self.suspended_by, Some(thread) if thread != current)
+    }
+
+    fn hidden_owner(&self) -> Option<usize> {
+        self.hidden.as_ref().map(|block| block.owner)
+    }
+
+    /// Erase currently displayed progress bars, the cursor is then placed
+    /// where they started.
+    fn erase(&self) -> io::Result<()> {
+        if let Some(block) = &self.block {
+            let mut stream = block.output_stream.get();
+            stream.write_all(block.erase_sequence().as_bytes())?;
+            stream.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Draw progress bars again after they were erased.
+    fn restore(&self) -> io::Result<()> {
+        if let Some(block) = &self.block {
+            let mut stream = block.output_stream.get();
+            stream.write_all(block.text.as_bytes())?;
+            stream.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Resume drawing when dropped, even if the suspending closure panicked.
+struct Suspended {
+    /// Block hidden by an outer suspension.
+    hidden: Option<Block>,
+    previous: Option<ThreadId>,
+}
+
+impl Drop for Suspended {
+    fn drop(&mut self) {
+        let mut screen = lock();
+        screen.suspended_by = self.previous;
+
+        for (output_stream, text) in mem::take(&mut screen.deferred) {
+            screen.println(&output_stream, &text).ok();
+        }
+
+        let hidden = mem::replace(&mut screen.hidden, self.hidden.take());
+
+        // Progress bars may have been drawn by this thread in the meantime
+        if screen.block.is_none() {
+            screen.block = hidden;
+            screen.restore().ok();
+        }
+
+        RESUMED.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iter_progress::AsProgressIterator;
+    use crate::multi_progress::MultiProgress;
+    use crate::progress::{DisplayMode, WithProgress};
+    use crate::utils::buffer_output;
+
+    #[test]
+    fn release_dropped_progress_bars() {
+        let (output_stream, output) = buffer_output();
+
+        let progress = || {
+            (0..100)
+                .progress()
+                .with_display_mode(DisplayMode::Terminal)
+                .with_output_stream(output_stream.clone())
+        };
+
+        // A progress bar left before the end is not drawn again
+        progress().take_while(|&i| i < 50).for_each(drop);
+        lock().println(&output_stream, "first").unwrap();
+        assert!(output().ends_with("\nfirst\n"));
+
+        // Neither is the region of a container with such a progress bar
+        let multi = MultiProgress::new().with_output_stream(output_stream.clone());
+        multi.add(progress()).for_each(drop);
+        multi.add(progress()).take_while(|&i| i < 50).for_each(drop);
+        lock().println(&output_stream, "second").unwrap();
+        assert!(output().ends_with("\nsecond\n"));
+    }
+
+    #[test]
+    fn release_after_removing_unfinished_progress_bar() {
+        let (output_stream, output) = buffer_output();
+
+        let progress = || {
+            (0..2)
+                .progress()
+                .with_display_mode(DisplayMode::Terminal)
+                .with_output_stream(output_stream.clone())
+        };
+
+        // Removing the last unfinished progress bar releases the region
+        let multi = MultiProgress::new().with_output_stream(output_stream.clone());
+        let finished = multi.add(progress());
+        let mut unfinished = multi.add(progress());
+        finished.for_each(drop);
+        unfinished.next();
+        multi.remove(&mut unfinished).unwrap();
+
+        lock().println(&output_stream, "third").unwrap();
+        assert!(output().ends_with("\nthird\n"));
+    }
+
+    #[test]
+    fn no_region_for_progress_bars_printing_lines() {
+        let (output_stream, output) = buffer_output();
+
+        // Progress bars in log mode print their own lines
+        let multi = MultiProgress::new().with_output_stream(output_stream.clone());
+        multi
+            .add(
+                (0..100)
+                    .progress()
+                    .with_display_mode(DisplayMode::Log)
+                    .with_output_stream(output_stream.clone()),
+            )
+            .for_each(drop);
+
+        let output = output();
+        assert!(output.ends_with('\n') && !output.ends_with("\n\n"));
+        assert!(!output.contains('\r'));
+    }
+
+    #[test]
+    fn draw_from_other_threads_while_suspended() {
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let (output_stream, output) = buffer_output();
+        let (done, finished) = mpsc::channel();
+
+        thread::spawn(move || {
+            let progress = || {
+                (0..1_000)
+                    .progress()
+                    .with_display_mode(DisplayMode::Terminal)
+                    .with_refresh_delay(Duration::ZERO)
+                    .with_output_stream(output_stream.clone())
+            };
+
+            let multi = MultiProgress::new().with_output_stream(output_stream.clone());
+            let mut first = multi.add(progress());
+            let second = multi.add(progress());
+            let worker = thread::spawn(move || second.for_each(|_| thread::yield_now()));
+
+            // The other thread may wait for the screen while it holds the
+            // lock of the container
+            suspend(|| {
+                let len = output().len();
+                thread::sleep(Duration::from_millis(50));
+                assert_eq!(output().len(), len);
+                first.next();
+            });
+
+            first.for_each(drop);
+            worker.join().unwrap();
+            done.send(output()).unwrap();
+        });
+
+        let output = finished
+            .recv_timeout(Duration::from_secs(10))
+            .expect("deadlock while suspended");
+
+        let last_draw = output.rsplit("\x1b[J").next().unwrap();
+        assert_eq!(last_draw.matches("1000/1000").count(), 2);
+    }
+}
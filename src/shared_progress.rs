@@ -0,0 +1,204 @@
+//! Defines a handle to a progress bar which can be shared between threads.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use crate::progress::WithProgress;
+use crate::step_progress::{StepProgress, WithStepProgress};
+
+/// A cloneable handle to a progress bar which can only step forward, which
+/// can be used concurrently by several threads.
+///
+/// Steps are counted using an atomic counter, the progress bar is only
+/// locked when it needs to be redrawn, by the thread which made progress
+/// when the refresh delay decayed. The progress bar is finished when the
+/// last handle is dropped.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::prelude::*;
+/// use prog_rs::{SharedProgress, StepProgress};
+///
+/// let progress = SharedProgress::new(
+///     StepProgress::new()
+///         .with_max_step(4_000)
+///         .with_prefix("Working ..."),
+/// );
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             for _ in 0..1_000 {
+///                 progress.inc(1);
+///             }
+///         });
+///     }
+/// });
+///
+/// progress.finish();
+/// ```
+#[derive(Clone, Debug)]
+pub struct SharedProgress {
+    inner: Arc<SharedInner>,
+}
+
+#[derive(Debug)]
+struct SharedInner {
+    /// Time of creation, other instants are stored relatively to it.
+    origin: Instant,
+
+    /// Number of nanoseconds after `origin` before the next redraw.
+    next_draw: AtomicU64,
+
+    /// Steps that were not yet sent to the progress bar.
+    pending: AtomicUsize,
+
+    step_progress: Mutex<StepProgress>,
+}
+
+impl SharedProgress {
+    /// Share a progress bar between threads.
+    pub fn new(step_progress: StepProgress) -> Self {
+        Self {
+            inner: Arc::new(SharedInner {
+                origin: Instant::now(),
+                next_draw: AtomicU64::new(0),
+                pending: AtomicUsize::new(0),
+                step_progress: Mutex::new(step_progress),
+            }),
+        }
+    }
+
+    /// Make progress for `count` iterations and redraw if necessary.
+    pub fn inc(&self, count: usize) {
+        self.inner.pending.fetch_add(count, Ordering::Relaxed);
+
+        if self.inner.nanos() < self.inner.next_draw.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // Another thread is already redrawing, it will take these steps
+        // into account next time
+        if let Ok(mut step_progress) = self.inner.step_progress.try_lock() {
+            self.inner.flush(&mut step_progress);
+        }
+    }
+
+    /// Get current step.
+    pub fn cur_step(&self) -> usize {
+        let mut step_progress = self.lock();
+        self.inner.flush(&mut step_progress);
+        step_progress.cur_step()
+    }
+
+    /// End iterations and redraw.
+    pub fn finish(&self) {
+        let mut step_progress = self.lock();
+        self.inner.flush(&mut step_progress);
+        step_progress.finish();
+    }
+
+    /// Get exclusive access to the progress bar, for example to change its
+    /// settings. Other threads won't redraw while it is locked.
+    pub fn lock(&self) -> MutexGuard<'_, StepProgress> {
+        self.inner.step_progress.lock().unwrap()
+    }
+}
+
+impl From<StepProgress> for SharedProgress {
+    fn from(step_progress: StepProgress) -> Self {
+        Self::new(step_progress)
+    }
+}
+
+impl SharedInner {
+    fn nanos(&self) -> u64 {
+        self.origin.elapsed().as_nanos() as u64
+    }
+
+    /// Send pending steps to the progress bar, which may redraw it.
+    fn flush(&self, step_progress: &mut StepProgress) {
+        let pending = self.pending.swap(0, Ordering::Relaxed);
+        step_progress.step(pending);
+
+        let next_draw =
+            self.nanos() + step_progress.get_progress().refresh_delay().as_nanos() as u64;
+        self.next_draw.store(next_draw, Ordering::Relaxed);
+    }
+}
+
+impl Drop for SharedInner {
+    /// Draw steps which are still pending and finish the progress bar.
+    fn drop(&mut self) {
+        let pending = *self.pending.get_mut();
+        let step_progress = self
+            .step_progress
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        step_progress.step(pending);
+        step_progress.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::progress::{DisplayMode, OutputStream};
+    use crate::style::ColorMode;
+    use crate::utils::{buffer_output, sink_output};
+
+    fn step_progress(output_stream: OutputStream) -> StepProgress {
+        StepProgress::new()
+            .with_max_step(4_000)
+            .with_template("{percent} {pos}/{total}".parse().unwrap())
+            .with_color_mode(ColorMode::Never)
+            .with_display_mode(DisplayMode::Log)
+            .with_output_stream(output_stream)
+    }
+
+    #[test]
+    fn inc_from_several_threads() {
+        let progress = SharedProgress::new(step_progress(sink_output()));
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1_000 {
+                        progress.inc(1);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(progress.cur_step(), 4_000);
+        progress.finish();
+        assert_eq!(progress.lock().render(80), "100.0% 4000/4000");
+    }
+
+    #[test]
+    fn finish_when_last_handle_is_dropped() {
+        let (output_stream, output) = buffer_output();
+
+        // Steps are not drawn before the progress bar is finished
+        let progress = SharedProgress::new(
+            step_progress(output_stream).with_refresh_delay(Duration::from_secs(3600)),
+        );
+
+        let other = progress.clone();
+        progress.inc(1);
+        other.inc(2_000);
+        progress.inc(1_999);
+
+        drop(progress);
+        assert!(!output().contains("4000/4000"));
+
+        drop(other);
+        assert!(output().ends_with("100.0% 4000/4000\n"));
+    }
+}
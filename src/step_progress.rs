@@ -1,20 +1,44 @@
 //! Defines wrapper for a progress bar which can only step forward.
 
-const HISTORY_DURATION: u64 = 10_000; // in milliseconds
-
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
-use crate::progress::{Progress, WithProgress};
-use crate::utils::convert_to_unit;
+use crate::estimator::{EmaSpeed, Estimator, EstimatorState, GlobalAverage};
+use crate::progress::{Progress, Segment, Stats, WithProgress};
+
+/// How the speed is computed from steps made during the speed window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpeedSmoothing {
+    /// Average speed over the whole window.
+    Mean,
+
+    /// Exponential moving average of speeds measured between two draws,
+    /// with given smoothing factor between 0 and 1, which is the weight
+    /// given to the last second: a higher factor gives more weight to recent
+    /// speeds. This ignores the speed window, the factor is clamped to
+    /// the range 0 to 1. With a factor of 0, no speed is forgotten: this
+    /// is the average of speeds since the beginning, weighted by their
+    /// duration.
+    Ema(f32),
+
+    /// Median of speeds measured between two draws during the window,
+    /// weighted by the time between draws, which ignores occasional bursts
+    /// or stalls.
+    Median,
+}
 
 /// A wrapper for a progress bar which can only step forward.
 #[derive(Clone, Debug)]
 pub struct StepProgress {
     cur_step: usize,
+    ema_speed: EmaSpeed,
+    estimator: Box<dyn Estimator>,
+    finished: bool,
     humanize: bool,
     max_step: Option<usize>,
     progress: Progress,
+    speed_smoothing: SpeedSmoothing,
+    speed_window: Duration,
     time_start: Instant,
     time_history: VecDeque<(Instant, usize)>,
     unit: String,
@@ -24,20 +48,58 @@ impl StepProgress {
     pub fn new() -> Self {
         Self {
             cur_step: 0,
+            ema_speed: EmaSpeed::default(),
+            estimator: Box::new(GlobalAverage),
+            finished: false,
             humanize: false,
             max_step: None,
             progress: Progress::new(),
+            speed_smoothing: SpeedSmoothing::Mean,
+            speed_window: Duration::from_secs(10),
             time_start: Instant::now(),
             time_history: vec![(Instant::now(), 0)].into(),
             unit: String::new(),
         }
     }
 
-    /// Compute the current average speed of iterations.
+    /// Compute the current speed of iterations, over the speed window.
     pub fn speed(&self) -> f32 {
-        let (old_time, old_iter) = *self.time_history.front().unwrap();
-        let (cur_time, cur_iter) = (Instant::now(), self.cur_step);
-        (cur_iter - old_iter) as f32 / (cur_time - old_time).as_secs_f32()
+        let smoothed = match self.speed_smoothing {
+            SpeedSmoothing::Mean => None,
+            SpeedSmoothing::Ema(_) => self.ema_speed.get().map(|speed| speed as f32),
+            SpeedSmoothing::Median => {
+                let mut samples: Vec<_> = (1..self.time_history.len())
+                    .filter_map(|i| self.sample(i))
+                    .collect();
+
+                samples.sort_by(|(speed_1, _), (speed_2, _)| speed_1.total_cmp(speed_2));
+                let half = samples.iter().map(|(_, secs)| secs).sum::<f32>() / 2.;
+                let mut acc = 0.;
+
+                samples
+                    .into_iter()
+                    .find(|(_, secs)| {
+                        acc += secs;
+                        acc >= half
+                    })
+                    .map(|(speed, _)| speed)
+            }
+        };
+
+        smoothed.unwrap_or_else(|| {
+            let (old_time, old_iter) = *self.time_history.front().unwrap();
+            let (cur_time, cur_iter) = (Instant::now(), self.cur_step);
+            cur_iter.saturating_sub(old_iter) as f32 / (cur_time - old_time).as_secs_f32()
+        })
+    }
+
+    /// Compute the speed between the `i`-th draw of the history and the
+    /// previous one, with the number of seconds between them.
+    fn sample(&self, i: usize) -> Option<(f32, f32)> {
+        let (old_time, old_iter) = self.time_history[i - 1];
+        let (cur_time, cur_iter) = self.time_history[i];
+        let secs = (cur_time - old_time).as_secs_f32();
+        (secs > 0.).then(|| (cur_iter.saturating_sub(old_iter) as f32 / secs, secs))
     }
 
     /// Compute the total average speed of iterations.
@@ -47,32 +109,89 @@ impl StepProgress {
 
     /// Make progress for `count` iterations and redraw if necessary.
     pub fn step(&mut self, count: usize) {
-        self.cur_step += count;
+        self.set_step(self.cur_step + count)
+    }
+
+    /// Move to given step and redraw if necessary, the new step may be lower
+    /// than current step, which resumes iterations if they were ended.
+    pub fn set_step(&mut self, step: usize) {
+        if step < self.cur_step {
+            // Forget history about steps that will be made again
+            self.time_history
+                .retain(|&(_, hist_step)| hist_step <= step);
+
+            if self.time_history.is_empty() {
+                self.time_history.push_back((Instant::now(), step));
+            }
+
+            self.finished = false;
+        }
+
+        self.cur_step = step;
 
         if self.progress.need_refresh() {
             self.draw(false);
+        } else {
+            // Keep the thread redrawing the progress bar up to date
+            self.progress.sync_step(step);
         }
     }
 
-    /// End iterations and redraw.
+    /// End iterations and redraw, does nothing if iterations were already
+    /// ended.
     pub fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        self.finished = true;
         self.draw(true);
     }
 
-    fn draw(&mut self, finished: bool) {
-        self.time_history
-            .push_back((Instant::now(), self.cur_step + 1));
+    /// Build the line representing the current state of the progress bar,
+    /// fitting in `width` columns, without displaying it.
+    pub fn render(&self, width: usize) -> String {
+        self.snapshot().render(width)
+    }
 
-        let nb_steps = self.max_step.unwrap_or(self.cur_step);
+    /// Split the line representing the current state of the progress bar,
+    /// fitting in `width` columns, into styled pieces of text.
+    pub fn segments(&self, width: usize) -> Vec<Segment> {
+        self.snapshot().segments(width)
+    }
+
+    /// Get a copy of the inner progress bar, updated with current state.
+    fn snapshot(&self) -> Progress {
+        let (fraction, stats) = self.state();
+        let mut progress = self.progress.clone();
+        progress.set_progress(fraction);
+        progress.set_stats(stats);
+        progress
+    }
+
+    /// Compute current progression and numeric informations.
+    fn state(&self) -> (Option<f32>, Stats) {
+        let stats = self.stats(self.finished);
+
+        if self.finished {
+            return (Some(1.0), stats);
+        }
+
+        let fraction = stats
+            .total
+            .map(|nb_steps| self.cur_step as f32 / nb_steps as f32);
+
+        (fraction, stats)
+    }
 
-        let duration = {
+    /// Compute numeric informations about current state.
+    fn stats(&self, finished: bool) -> Stats {
+        // The total is known once finished, even if it was not specified
+        let nb_steps = {
             if finished {
-                self.time_start.elapsed()
+                Some(self.max_step.unwrap_or(self.cur_step))
             } else {
-                Duration::from_secs_f64(
-                    (nb_steps - self.cur_step) as f64 / (1. + self.cur_step as f64)
-                        * self.time_start.elapsed().as_secs_f64(),
-                )
+                self.max_step
             }
         };
 
@@ -85,51 +204,57 @@ impl StepProgress {
             }
         };
 
-        let (speed, unit_prefix) = convert_to_unit(speed);
+        let eta = match nb_steps {
+            Some(nb_steps) if !finished => self.estimator.estimate(&EstimatorState {
+                pos: self.cur_step,
+                total: nb_steps,
+                elapsed: self.time_start.elapsed(),
+                history: &self.time_history,
+                rate: speed,
+            }),
+            _ => None,
+        };
 
-        // Compute current state with unit
-        let displayed_precision = if self.humanize { 2 } else { 0 };
+        Stats {
+            pos: self.cur_step,
+            total: nb_steps,
+            elapsed: self.time_start.elapsed(),
+            eta,
+            rate: speed,
+            unit: self.unit.clone(),
+            humanize: self.humanize,
+        }
+    }
 
-        let (displayed_cur, displayed_cur_unit) = {
-            if self.humanize {
-                convert_to_unit(self.cur_step as f32)
-            } else {
-                (self.cur_step as f32, "")
-            }
-        };
+    fn draw(&mut self, finished: bool) {
+        let now = Instant::now();
+        self.time_history.push_back((now, self.cur_step));
+        self.estimator.record(now, self.cur_step);
+
+        if let (SpeedSmoothing::Ema(alpha), Some((speed, secs))) = (
+            self.speed_smoothing,
+            self.sample(self.time_history.len() - 1),
+        ) {
+            self.ema_speed
+                .update(alpha.into(), speed.into(), secs.into());
+        }
 
-        let (displayed_max, displayed_max_unit) = {
-            if self.humanize {
-                convert_to_unit(nb_steps as f32)
-            } else {
-                (nb_steps as f32, "")
-            }
-        };
+        let stats = self.stats(finished);
+        let nb_steps = stats.total;
+        self.progress.set_stats(stats);
 
-        self.progress.set_extra_infos(format!(
-            "{:.precision$}{}{unit}/{:>.precision$}{}{unit}, {:.1?} ({:.1} {}{unit}/s) ",
-            displayed_cur,
-            displayed_cur_unit,
-            displayed_max,
-            displayed_max_unit,
-            duration,
-            speed,
-            unit_prefix,
-            precision = displayed_precision,
-            unit = self.unit
-        ));
-
-        if finished {
-            self.progress.finished().ok();
-        } else {
-            self.progress
+        match nb_steps {
+            _ if finished => self.progress.finished().ok(),
+            Some(nb_steps) => self
+                .progress
                 .update(self.cur_step as f32 / nb_steps as f32)
-                .ok();
-        }
+                .ok(),
+            None => self.progress.update_indeterminate().ok(),
+        };
 
-        // Trim history to get a window of size ~10s
+        // Trim history to the speed window
         while self.time_history.back().unwrap().0 - self.time_history.front().unwrap().0
-            > Duration::from_millis(HISTORY_DURATION)
+            > self.speed_window
         {
             self.time_history.pop_front();
         }
@@ -173,7 +298,55 @@ pub trait WithStepProgress: Sized {
         self
     }
 
-    /// Update expected max step.
+    /// Change how the remaining time is estimated, `GlobalAverage` is used
+    /// by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use prog_rs::prelude::*;
+    /// use prog_rs::Ema;
+    ///
+    /// for _ in (0..1_000).progress().with_estimator(Ema::new(0.2)) {}
+    /// ```
+    fn with_estimator<E>(mut self, estimator: E) -> Self
+    where
+        E: Estimator + 'static,
+    {
+        self.get_step_progress().estimator = Box::new(estimator);
+        self
+    }
+
+    /// Change the duration of the window over which the speed is computed,
+    /// it is 10 seconds by default.
+    fn with_speed_window(mut self, speed_window: Duration) -> Self {
+        self.get_step_progress().speed_window = speed_window;
+        self
+    }
+
+    /// Change how the speed is computed over the speed window, which is
+    /// also used by the `SlidingWindow` estimator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use prog_rs::prelude::*;
+    /// use prog_rs::SpeedSmoothing;
+    /// use std::time::Duration;
+    ///
+    /// for _ in (0..1_000)
+    ///     .progress()
+    ///     .with_speed_window(Duration::from_secs(60))
+    ///     .with_speed_smoothing(SpeedSmoothing::Ema(0.3))
+    /// {}
+    /// ```
+    fn with_speed_smoothing(mut self, speed_smoothing: SpeedSmoothing) -> Self {
+        self.get_step_progress().speed_smoothing = speed_smoothing;
+        self
+    }
+
+    /// Update expected max step. If it is not specified, an animation is
+    /// displayed instead of the progress bar.
     fn with_max_step(mut self, max_step: usize) -> Self {
         self.get_step_progress().max_step = Some(max_step);
         self
@@ -209,3 +382,103 @@ where
         &mut self.get_step_progress().progress
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::ColorMode;
+    use crate::utils::sink_output;
+
+    #[test]
+    fn history_records_reached_steps() {
+        let mut step_progress = StepProgress::new()
+            .with_max_step(10)
+            .with_output_stream(sink_output());
+
+        step_progress.step(5);
+        assert_eq!(step_progress.time_history.back().unwrap().1, 5);
+
+        step_progress.finish();
+        assert_eq!(step_progress.time_history.back().unwrap().1, 5);
+    }
+
+    /// Build a progress bar displayed nowhere, which went through given
+    /// steps, with their age.
+    fn with_history(history: &[(u64, usize)]) -> StepProgress {
+        let now = Instant::now();
+        let mut step_progress = StepProgress::new().with_output_stream(sink_output());
+
+        step_progress.time_history = history
+            .iter()
+            .map(|&(age, step)| (now - Duration::from_secs(age), step))
+            .collect();
+
+        step_progress.cur_step = history.last().unwrap().1;
+        step_progress
+    }
+
+    fn assert_speed(speed: f32, expected: f32) {
+        assert!((speed - expected).abs() < 0.1, "{} != {}", speed, expected);
+    }
+
+    #[test]
+    fn median_speed() {
+        // A burst of 80 steps per second is ignored
+        let step_progress = with_history(&[(4, 0), (3, 10), (2, 20), (1, 100), (0, 110)])
+            .with_speed_smoothing(SpeedSmoothing::Median);
+        assert_speed(step_progress.speed(), 10.);
+
+        // Speeds are weighted by their duration
+        let step_progress = with_history(&[(5, 0), (4, 30), (3, 60), (0, 90)])
+            .with_speed_smoothing(SpeedSmoothing::Median);
+        assert_speed(step_progress.speed(), 10.);
+    }
+
+    #[test]
+    fn ema_speed() {
+        let mut step_progress =
+            with_history(&[(2, 0)]).with_speed_smoothing(SpeedSmoothing::Ema(0.5));
+
+        // Speed of 10 steps per second during 2 seconds
+        step_progress.cur_step = 20;
+        step_progress.draw(false);
+        assert_speed(step_progress.speed(), 10.);
+
+        // Speed of 30 steps per second during the last second
+        *step_progress.time_history.back_mut().unwrap() =
+            (Instant::now() - Duration::from_secs(1), 20);
+        step_progress.cur_step = 50;
+        step_progress.draw(false);
+        assert_speed(step_progress.speed(), 18.75 / 0.875);
+    }
+
+    #[test]
+    fn history_is_trimmed_to_speed_window() {
+        let history: Vec<_> = (0..10).map(|i| (10 - i, 10 * i as usize)).collect();
+        let mut step_progress = with_history(&history).with_speed_window(Duration::from_secs(5));
+
+        step_progress.cur_step = 100;
+        step_progress.draw(false);
+        assert_eq!(step_progress.time_history.len(), 5);
+        assert_eq!(step_progress.time_history.front().unwrap().1, 60);
+        assert_speed(step_progress.speed(), 10.);
+    }
+
+    #[test]
+    fn switch_to_known_total() {
+        let mut step_progress = StepProgress::new()
+            .with_bar_width(9)
+            .with_color_mode(ColorMode::Never)
+            .with_output_stream(sink_output());
+
+        // A spinner is displayed instead of the percentage
+        step_progress.step(5);
+        let line = step_progress.render(80);
+        assert!(!line.contains('%'), "{}", line);
+        assert!(line.contains("] 5, "), "{}", line);
+
+        step_progress.set_max_step(10);
+        let line = step_progress.render(80);
+        assert!(line.starts_with("  50.0% [=====>    ] 5/10, "), "{}", line);
+    }
+}
@@ -0,0 +1,156 @@
+//! Defines a wrapper around streams to display a progress bar, requires the
+//! `futures` feature.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::{ready, Stream};
+use pin_project_lite::pin_project;
+
+use crate::step_progress::{StepProgress, WithStepProgress};
+use crate::utils::expected_len;
+
+pin_project! {
+    /// A wrapper stream arround another stream which adds a progress bar.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::executor::block_on;
+    /// use futures::stream::{self, StreamExt};
+    /// use prog_rs::prelude::*;
+    ///
+    /// let sum: u64 = block_on(
+    ///     stream::iter(0..1_000u64)
+    ///         .progress()
+    ///         .with_prefix("Receiving ...")
+    ///         .fold(0, |acc, x| async move { acc + x }),
+    /// );
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct StreamProgress<S> {
+        #[pin]
+        inner: S,
+        step_progress: StepProgress,
+    }
+}
+
+impl<S> StreamProgress<S>
+where
+    S: Stream,
+{
+    fn new(inner: S) -> Self {
+        let mut step_progress = StepProgress::new();
+
+        if let Some(max_step) = expected_len(inner.size_hint()) {
+            step_progress.set_max_step(max_step);
+        }
+
+        Self {
+            inner,
+            step_progress,
+        }
+    }
+}
+
+impl<S> Stream for StreamProgress<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        let item = ready!(this.inner.as_mut().poll_next(cx));
+
+        match item {
+            None => this.step_progress.finish(),
+            Some(_) => {
+                let cur_step = this.step_progress.cur_step();
+
+                match expected_len(this.inner.size_hint()) {
+                    Some(remaining) => this.step_progress.set_max_step(cur_step + remaining + 1),
+                    // A previous size hint may have underestimated the total
+                    None => {
+                        if let Some(max_step) = this.step_progress.max_step() {
+                            this.step_progress.set_max_step(max_step.max(cur_step + 1));
+                        }
+                    }
+                }
+
+                this.step_progress.step(1)
+            }
+        }
+
+        Poll::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<S> WithStepProgress for StreamProgress<S> {
+    fn get_step_progress(&mut self) -> &mut StepProgress {
+        &mut self.step_progress
+    }
+}
+
+//  _____                      _____          _ _
+// |  ___| __ ___  _ __ ___   |_   _| __ __ _(_) |_
+// | |_ | '__/ _ \| '_ ` _ \    | || '__/ _` | | __|
+// |  _|| | | (_) | | | | | |   | || | | (_| | | |_
+// |_|  |_|  \___/|_| |_| |_|   |_||_|  \__,_|_|\__|
+//
+
+pub trait AsStreamProgress<S>
+where
+    S: Stream,
+{
+    fn progress(self) -> StreamProgress<S>;
+}
+
+impl<S> AsStreamProgress<S> for S
+where
+    S: Stream,
+{
+    fn progress(self) -> StreamProgress<S> {
+        StreamProgress::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::future;
+    use futures::stream::{self, StreamExt};
+
+    use super::*;
+    use crate::progress::WithProgress;
+    use crate::utils::sink_output;
+
+    #[test]
+    fn count_items_of_pinned_stream() {
+        // Streams built from async blocks can't be moved once polled
+        let items = stream::once(async { 1 }).chain(stream::once(async { 2 }));
+
+        let mut stream = Box::pin(items.progress().with_output_stream(sink_output()));
+
+        assert_eq!(block_on(stream.as_mut().collect::<Vec<_>>()), [1, 2]);
+        assert_eq!(stream.as_mut().project().step_progress.cur_step(), 2);
+    }
+
+    #[test]
+    fn total_is_never_below_current_step() {
+        let items =
+            stream::iter(0..10).chain(stream::iter(0..10).filter(|x| future::ready(x % 2 == 0)));
+
+        let mut stream = items.progress().with_output_stream(sink_output());
+
+        while block_on(stream.next()).is_some() {
+            assert!(stream.cur_step() <= stream.max_step().unwrap());
+        }
+
+        assert_eq!((stream.cur_step(), stream.max_step()), (15, Some(15)));
+    }
+}
@@ -0,0 +1,160 @@
+//! Defines styles used to color the elements of a progress bar.
+
+use std::env;
+
+/// A terminal color.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+
+    /// A color from the 256 colors palette.
+    Fixed(u8),
+
+    /// A true color, which is not supported by all terminals.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Get the parameters of the SGR sequence for this color, `base` is 30
+    /// for foreground colors and 40 for background colors.
+    fn sgr(self, base: u8) -> String {
+        use Color::*;
+        match self {
+            Black => base.to_string(),
+            Red => (base + 1).to_string(),
+            Green => (base + 2).to_string(),
+            Yellow => (base + 3).to_string(),
+            Blue => (base + 4).to_string(),
+            Magenta => (base + 5).to_string(),
+            Cyan => (base + 6).to_string(),
+            White => (base + 7).to_string(),
+            Fixed(code) => format!("{};5;{}", base + 8, code),
+            Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        }
+    }
+}
+
+/// Decide when colors are enabled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorMode {
+    /// Enable colors if the output stream is a terminal and the `NO_COLOR`
+    /// environment variable is not set.
+    Auto,
+
+    /// Always enable colors.
+    Always,
+
+    /// Never enable colors.
+    Never,
+}
+
+impl ColorMode {
+    pub(crate) fn enabled(self, is_terminal: bool) -> bool {
+        match self {
+            ColorMode::Auto => {
+                is_terminal && env::var_os("NO_COLOR").map_or(true, |val| val.is_empty())
+            }
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        }
+    }
+}
+
+/// The style of a piece of text, which can be built using chained calls.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::prelude::*;
+/// use prog_rs::{Color, Style};
+///
+/// for _ in (0..1_000)
+///     .progress()
+///     .with_style_prefix(Style::new().bold())
+///     .with_style_body(Style::new().fg(Color::Green))
+///     .with_style_extra(Style::new().dim())
+/// {}
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+}
+
+impl Style {
+    /// Create a style which doesn't alter text.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Change the color of the text.
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Change the color behind the text.
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Display the text in bold.
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Display the text with a lower intensity.
+    pub fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    /// Display the text in italic.
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    /// Surround a text with the escape sequences applying this style.
+    pub fn paint(&self, text: &str) -> String {
+        let mut params = Vec::new();
+
+        if self.bold {
+            params.push("1".to_string());
+        }
+
+        if self.dim {
+            params.push("2".to_string());
+        }
+
+        if self.italic {
+            params.push("3".to_string());
+        }
+
+        if let Some(fg) = self.fg {
+            params.push(fg.sgr(30));
+        }
+
+        if let Some(bg) = self.bg {
+            params.push(bg.sgr(40));
+        }
+
+        if params.is_empty() || text.is_empty() {
+            return text.to_string();
+        }
+
+        format!("\x1b[{}m{}\x1b[0m", params.join(";"), text)
+    }
+}
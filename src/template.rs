@@ -0,0 +1,321 @@
+//! Defines templates describing the layout of a progress bar's line.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::utils::text_width;
+
+/// A value that can be displayed by a template, written `{name}` in it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Field {
+    /// The text set with `with_prefix`, it will be truncated if the line
+    /// doesn't fit in the display.
+    Prefix,
+
+    /// The progress bar itself.
+    Bar,
+
+    /// The percentage of progression.
+    Percent,
+
+    /// An animation displayed while the progression is unknown.
+    Spinner,
+
+    /// The current step.
+    Pos,
+
+    /// The expected number of steps.
+    Total,
+
+    /// The time elapsed since the beginning.
+    Elapsed,
+
+    /// The estimated remaining time.
+    Eta,
+
+    /// The estimated remaining time, or the elapsed time once it is finished.
+    Time,
+
+    /// The speed of iterations.
+    Rate,
+
+    /// The text set with `with_extra_infos`.
+    Extra,
+
+    /// Blank space expanding to make the line fill the display width.
+    Fill,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        use Field::*;
+        match name {
+            "prefix" => Some(Prefix),
+            "bar" => Some(Bar),
+            "percent" => Some(Percent),
+            "spinner" => Some(Spinner),
+            "pos" => Some(Pos),
+            "total" => Some(Total),
+            "elapsed" => Some(Elapsed),
+            "eta" => Some(Eta),
+            "time" => Some(Time),
+            "rate" => Some(Rate),
+            "extra" => Some(Extra),
+            "fill" => Some(Fill),
+            _ => None,
+        }
+    }
+}
+
+/// Alignment of a field inside of its width.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Alignment {
+    /// Pad the field with spaces on the right, written `{name:<width}`.
+    Left,
+
+    /// Pad the field with spaces on the left, written `{name:>width}`.
+    Right,
+
+    /// Pad the field with spaces on both sides, written `{name:^width}`.
+    Center,
+}
+
+impl Alignment {
+    /// Pad a text with spaces until it reaches `width` columns.
+    pub(crate) fn pad(self, text: &str, width: usize) -> String {
+        let len = text_width(text);
+
+        if len >= width {
+            return text.to_string();
+        }
+
+        let (left, right) = match self {
+            Alignment::Left => (0, width - len),
+            Alignment::Right => (width - len, 0),
+            Alignment::Center => ((width - len) / 2, width - len - (width - len) / 2),
+        };
+
+        format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+    }
+}
+
+/// A piece of a template.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Chunk {
+    Text(String),
+    Field {
+        field: Field,
+        width: Option<usize>,
+        alignment: Alignment,
+    },
+}
+
+/// The layout of a progress bar's line.
+///
+/// A template is made of raw text and of fields written between braces,
+/// optionally followed by an alignment (`<`, `>` or `^`) and a width. Braces
+/// can be escaped by doubling them.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::prelude::*;
+/// use prog_rs::Template;
+///
+/// let template: Template = "{prefix:<20} |{bar}| {percent:>6} {pos}/{total} {eta}"
+///     .parse()
+///     .unwrap();
+///
+/// for _ in (0..1_000).progress().with_template(template) {}
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Template {
+    chunks: Vec<Chunk>,
+}
+
+impl Template {
+    /// Parse a template from its textual representation.
+    pub fn parse(template: &str) -> Result<Self, TemplateError> {
+        let mut chunks = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut spec = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => spec.push(c),
+                            None => return Err(TemplateError::UnmatchedBrace),
+                        }
+                    }
+
+                    if !text.is_empty() {
+                        chunks.push(Chunk::Text(std::mem::take(&mut text)));
+                    }
+
+                    chunks.push(Self::parse_field(&spec)?);
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace),
+                _ => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            chunks.push(Chunk::Text(text));
+        }
+
+        Ok(Self { chunks })
+    }
+
+    fn parse_field(spec: &str) -> Result<Chunk, TemplateError> {
+        let (name, format) = match spec.find(':') {
+            Some(pos) => (&spec[..pos], &spec[pos + 1..]),
+            None => (spec, ""),
+        };
+
+        let field = Field::from_name(name.trim())
+            .ok_or_else(|| TemplateError::UnknownField(name.to_string()))?;
+
+        let (alignment, width) = match format.chars().next() {
+            Some('<') => (Alignment::Left, &format[1..]),
+            Some('>') => (Alignment::Right, &format[1..]),
+            Some('^') => (Alignment::Center, &format[1..]),
+            _ => (Alignment::Left, format),
+        };
+
+        let width = {
+            if width.is_empty() {
+                None
+            } else {
+                Some(
+                    width
+                        .parse()
+                        .map_err(|_| TemplateError::InvalidWidth(width.to_string()))?,
+                )
+            }
+        };
+
+        Ok(Chunk::Field {
+            field,
+            width,
+            alignment,
+        })
+    }
+
+    pub(crate) fn chunks(&self) -> &[Chunk] {
+        &self.chunks
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        Self::parse(template)
+    }
+}
+
+/// An error that occured while parsing a template.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TemplateError {
+    /// The name between braces is not a known field.
+    UnknownField(String),
+
+    /// The width of a field is not a number.
+    InvalidWidth(String),
+
+    /// A brace has no matching opening or closing brace.
+    UnmatchedBrace,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateError::UnknownField(name) => write!(f, "unknown field `{}`", name),
+            TemplateError::InvalidWidth(width) => write!(f, "invalid width `{}`", width),
+            TemplateError::UnmatchedBrace => write!(f, "unmatched brace in template"),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::{Progress, WithProgress};
+    use crate::style::ColorMode;
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Template::parse("{bar"), Err(TemplateError::UnmatchedBrace));
+        assert_eq!(Template::parse("bar}"), Err(TemplateError::UnmatchedBrace));
+        assert_eq!(
+            Template::parse("{foo}"),
+            Err(TemplateError::UnknownField("foo".to_string()))
+        );
+        assert_eq!(
+            Template::parse("{bar:>ten}"),
+            Err(TemplateError::InvalidWidth("ten".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_fields() {
+        let template = Template::parse("{prefix:^10}|{bar}").unwrap();
+
+        assert_eq!(
+            template.chunks(),
+            [
+                Chunk::Field {
+                    field: Field::Prefix,
+                    width: Some(10),
+                    alignment: Alignment::Center,
+                },
+                Chunk::Text("|".to_string()),
+                Chunk::Field {
+                    field: Field::Bar,
+                    width: None,
+                    alignment: Alignment::Left,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_braces() {
+        let template = Template::parse("{{{percent}}} }}{{").unwrap();
+
+        assert_eq!(
+            template.chunks(),
+            [
+                Chunk::Text("{".to_string()),
+                Chunk::Field {
+                    field: Field::Percent,
+                    width: None,
+                    alignment: Alignment::Left,
+                },
+                Chunk::Text("} }{".to_string()),
+            ]
+        );
+
+        let progress = Progress::new()
+            .with_template(template)
+            .with_color_mode(ColorMode::Never);
+
+        assert_eq!(progress.render(80), "{0.0%} }{");
+    }
+}
@@ -0,0 +1,172 @@
+//! Defines a background thread redrawing a progress bar at regular
+//! intervals.
+
+use std::cmp::max;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::progress::Progress;
+
+/// Minimal delay between two redraws from the thread.
+const MIN_DELAY: Duration = Duration::from_millis(10);
+
+/// Published step when there is none since the last synchronization.
+const NO_STEP: usize = usize::MAX;
+
+/// Handle to the thread redrawing a progress bar, the thread is stopped when
+/// the handle is dropped.
+#[derive(Debug, Default)]
+pub(crate) struct Ticker {
+    state: Option<Arc<Mutex<TickerState>>>,
+
+    /// Last step made since the last synchronization, which is published
+    /// without locking the state.
+    step: Option<Arc<AtomicUsize>>,
+}
+
+impl Ticker {
+    /// Get the state shared with the thread, the thread is started if it is
+    /// not running yet.
+    pub(crate) fn state(&mut self, progress: &Progress) -> Arc<Mutex<TickerState>> {
+        let step = &mut self.step;
+
+        self.state
+            .get_or_insert_with(|| {
+                let state = Arc::new(Mutex::new(TickerState {
+                    last_draw: Instant::now(),
+                    progress: progress.clone(),
+                    stopped: false,
+                    synced_at: Instant::now(),
+                }));
+
+                let thread_state = state.clone();
+                let thread_step = Arc::new(AtomicUsize::new(NO_STEP));
+                *step = Some(thread_step.clone());
+                thread::spawn(move || run(thread_state, thread_step));
+                state
+            })
+            .clone()
+    }
+
+    /// Send a step to the thread without locking its state, it is displayed
+    /// on its next redraw.
+    pub(crate) fn publish_step(&self, step: usize) {
+        if let Some(published) = &self.step {
+            published.store(step, Ordering::Relaxed);
+        }
+    }
+
+    /// Forget the published step, must be called while the state is locked
+    /// after synchronizing it.
+    pub(crate) fn clear_step(&self) {
+        if let Some(published) = &self.step {
+            published.store(NO_STEP, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Clone for Ticker {
+    /// A clone of a progress bar starts its own thread when needed.
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Drop for Ticker {
+    fn drop(&mut self) {
+        if let Some(state) = &self.state {
+            state.lock().unwrap().stopped = true;
+        }
+    }
+}
+
+/// State of the progress bar, as seen by the thread.
+#[derive(Debug)]
+pub(crate) struct TickerState {
+    /// Last time the progress bar was drawn, by the thread or its owner.
+    pub(crate) last_draw: Instant,
+
+    /// A copy of the progress bar, as it was when last synchronized.
+    pub(crate) progress: Progress,
+
+    /// Set when the thread must exit.
+    pub(crate) stopped: bool,
+
+    /// Last time the copy of the progress bar was synchronized.
+    pub(crate) synced_at: Instant,
+}
+
+fn run(state: Arc<Mutex<TickerState>>, step: Arc<AtomicUsize>) {
+    let mut delay = Duration::from_millis(0);
+
+    loop {
+        thread::sleep(delay);
+        let mut state = state.lock().unwrap();
+        delay = max(state.progress.refresh_delay(), MIN_DELAY);
+
+        if state.stopped {
+            break;
+        }
+
+        let since_draw = state.last_draw.elapsed();
+
+        if since_draw < delay {
+            delay -= since_draw;
+            continue;
+        }
+
+        let published = step.swap(NO_STEP, Ordering::Relaxed);
+
+        if published != NO_STEP {
+            state.progress.set_pos(published);
+        }
+
+        let since_sync = state.synced_at.elapsed();
+        state.progress.redraw_after(since_sync).ok();
+        state.last_draw = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::progress::{DisplayMode, WithProgress};
+    use crate::step_progress::{StepProgress, WithStepProgress};
+    use crate::utils::buffer_output;
+
+    #[test]
+    fn redraw_during_slow_step() {
+        let (output_stream, output) = buffer_output();
+
+        let mut step_progress = StepProgress::new()
+            .with_max_step(2)
+            .with_template("{pos}/{total} {elapsed}".parse().unwrap())
+            .with_display_mode(DisplayMode::Terminal)
+            .with_refresh_delay(Duration::from_millis(20))
+            .with_steady_tick(true)
+            .with_output_stream(output_stream);
+
+        step_progress.step(1);
+        thread::sleep(Duration::from_millis(300));
+
+        // The elapsed time keeps moving while no step is made
+        let lines: HashSet<_> = output()
+            .split('\r')
+            .map(|line| line.trim_start_matches("\x1b[J"))
+            .filter(|line| line.starts_with("1/2 "))
+            .map(String::from)
+            .collect();
+
+        assert!(lines.len() >= 3, "{:?}", lines);
+        step_progress.finish();
+
+        // The thread is stopped once finished
+        let len = output().len();
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(output().len(), len);
+    }
+}
@@ -0,0 +1,345 @@
+//! Defines a `tracing` layer displaying progress bars for spans, requires
+//! the `tracing` feature.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::fmt::Write as _;
+use std::sync::{Mutex, PoisonError};
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::{LookupSpan, SpanRef};
+
+use crate::multi_progress::MultiProgress;
+use crate::progress::{OutputStream, Progress, WithProgress};
+use crate::screen;
+use crate::step_progress::{StepProgress, WithStepProgress};
+
+/// Field of a span which enables a progress bar, with the expected number
+/// of steps.
+const TOTAL_FIELD: &str = "progress.total";
+
+/// Field of an event or a span which moves its progress bar to given step.
+const POS_FIELD: &str = "progress.pos";
+
+/// Field of an event or a span which makes progress for given number of
+/// steps.
+const INC_FIELD: &str = "progress.inc";
+
+/// A layer which displays a progress bar for each span with a
+/// `progress.total` field, using the name of the span as a prefix.
+///
+/// The progress bar moves forward when the span records a `progress.inc` or
+/// a `progress.pos` field, or when such an event is emitted inside of the
+/// span. It is finished when the span is closed. Other events are printed
+/// above progress bars.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::ProgressLayer;
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry()
+///     .with(ProgressLayer::new())
+///     .init();
+///
+/// let span = tracing::info_span!("download", progress.total = 1_000);
+/// let _enter = span.enter();
+///
+/// for i in 0..1_000 {
+///     tracing::trace!(progress.inc = 1);
+///
+///     if i % 100 == 0 {
+///         tracing::warn!("reached {}", i);
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ProgressLayer {
+    multi: MultiProgress,
+    output_stream: OutputStream,
+    progress: Progress,
+}
+
+impl ProgressLayer {
+    /// Create a layer displaying progress bars and events on standard error.
+    pub fn new() -> Self {
+        Self {
+            multi: MultiProgress::new().with_output_stream(OutputStream::StdErr),
+            output_stream: OutputStream::StdErr,
+            progress: Progress::new(),
+        }
+    }
+
+    /// Change the stream progress bars and events are displayed in.
+    pub fn with_output_stream(mut self, output_stream: OutputStream) -> Self {
+        self.multi = self.multi.with_output_stream(output_stream.clone());
+        self.output_stream = output_stream;
+        self
+    }
+
+    /// Specify a progress bar to copy the configuration of for each span.
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Start a progress bar for a span.
+    fn start<S>(&self, span: &SpanRef<'_, S>, total: u64)
+    where
+        S: for<'a> LookupSpan<'a>,
+    {
+        let step_progress = self.multi.add(
+            StepProgress::new()
+                .with_progress(self.progress.clone())
+                .with_max_step(total as usize)
+                .with_prefix(span.name()),
+        );
+
+        span.extensions_mut().insert(Mutex::new(step_progress));
+    }
+}
+
+impl Default for ProgressLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Layer<S> for ProgressLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = ProgressVisitor::default();
+        attrs.record(&mut visitor);
+
+        if let (Some(total), Some(span)) = (visitor.total, ctx.span(id)) {
+            self.start(&span, total);
+            visitor.apply(&span);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+
+        let mut visitor = ProgressVisitor::default();
+        values.record(&mut visitor);
+
+        // The total may not be known when the span is created
+        let started = span.extensions().get::<Mutex<StepProgress>>().is_some();
+
+        if let (false, Some(total)) = (started, visitor.total) {
+            self.start(&span, total);
+        }
+
+        visitor.apply(&span);
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = ProgressVisitor::default();
+        event.record(&mut visitor);
+
+        if visitor.pos.is_some() || visitor.inc.is_some() {
+            // Update the closest span with a progress bar
+            if let Some(span) = ctx.event_scope(event).and_then(|mut scope| {
+                scope.find(|span| span.extensions().get::<Mutex<StepProgress>>().is_some())
+            }) {
+                visitor.apply(&span);
+            }
+
+            // Events which only carry progress are not printed
+            if visitor.message.is_empty() && visitor.fields.is_empty() {
+                return;
+            }
+        }
+
+        let metadata = event.metadata();
+        let text = format!(
+            "{:>5} {}: {}{}",
+            metadata.level(),
+            metadata.target(),
+            visitor.message,
+            visitor.fields
+        );
+
+        screen::lock().println(&self.output_stream, &text).ok();
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let step_progress = ctx
+            .span(&id)
+            .and_then(|span| span.extensions_mut().remove::<Mutex<StepProgress>>());
+
+        if let Some(step_progress) = step_progress {
+            step_progress
+                .into_inner()
+                .unwrap_or_else(PoisonError::into_inner)
+                .finish();
+        }
+    }
+}
+
+/// Collect progress fields of a span or an event, other fields are
+/// formatted.
+#[derive(Debug, Default)]
+struct ProgressVisitor {
+    total: Option<u64>,
+    pos: Option<u64>,
+    inc: Option<u64>,
+    message: String,
+    fields: String,
+}
+
+impl ProgressVisitor {
+    /// Update the progress bar of a span with collected fields.
+    fn apply<S>(&self, span: &SpanRef<'_, S>)
+    where
+        S: for<'a> LookupSpan<'a>,
+    {
+        let extensions = span.extensions();
+
+        let mut step_progress = match extensions.get::<Mutex<StepProgress>>() {
+            Some(step_progress) => step_progress.lock().unwrap_or_else(PoisonError::into_inner),
+            None => return,
+        };
+
+        if let Some(total) = self.total {
+            step_progress.set_max_step(total as usize);
+        }
+
+        if let Some(pos) = self.pos {
+            step_progress.set_step(pos as usize);
+        }
+
+        if let Some(inc) = self.inc {
+            step_progress.step(inc as usize);
+        }
+    }
+}
+
+impl Visit for ProgressVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            TOTAL_FIELD => self.total = Some(value),
+            POS_FIELD => self.pos = Some(value),
+            INC_FIELD => self.inc = Some(value),
+            _ => self.record_debug(field, &value),
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        match u64::try_from(value) {
+            Ok(value) => self.record_u64(field, value),
+            Err(_) => self.record_debug(field, &value),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            write!(self.message, "{:?}", value).ok();
+        } else {
+            write!(self.fields, " {}={:?}", field.name(), value).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+    use crate::progress::DisplayMode;
+    use crate::utils::buffer_output;
+
+    /// Run `f` with a progress layer printing a line for each draw, and get
+    /// printed lines.
+    fn with_layer<F: FnOnce()>(f: F) -> Vec<String> {
+        let (output_stream, output) = buffer_output();
+
+        let layer = ProgressLayer::new()
+            .with_output_stream(output_stream)
+            .with_progress(
+                Progress::new()
+                    .with_template("{prefix} {pos}/{total} {percent}".parse().unwrap())
+                    .with_display_mode(DisplayMode::Log)
+                    .with_log_delay(Duration::ZERO)
+                    .with_refresh_delay(Duration::ZERO),
+            );
+
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), f);
+        output().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn total_at_span_creation() {
+        let lines = with_layer(|| {
+            let span = tracing::info_span!("download", progress.total = 10);
+            let _enter = span.enter();
+            tracing::trace!(progress.inc = 3);
+            tracing::trace!(progress.pos = 7);
+        });
+
+        assert_eq!(
+            lines,
+            [
+                "download 3/10 30.0%",
+                "download 7/10 70.0%",
+                "download 7/10 100.0%"
+            ]
+        );
+    }
+
+    #[test]
+    fn total_recorded_later() {
+        let lines = with_layer(|| {
+            let span = tracing::info_span!(
+                "upload",
+                progress.total = tracing::field::Empty,
+                progress.pos = tracing::field::Empty,
+            );
+            span.in_scope(|| tracing::trace!(progress.inc = 1));
+            span.record("progress.total", 4);
+            span.in_scope(|| tracing::trace!(progress.inc = 1));
+            span.record("progress.pos", 3);
+        });
+
+        assert_eq!(
+            lines,
+            ["upload 1/4 25.0%", "upload 3/4 75.0%", "upload 3/4 100.0%"]
+        );
+    }
+
+    #[test]
+    fn print_events() {
+        let lines = with_layer(|| {
+            // Progress without a progress bar is ignored
+            tracing::trace!(progress.inc = 1);
+            tracing::warn!(progress.inc = 1, "no progress bar");
+
+            let span = tracing::info_span!("process", progress.total = 2);
+            let _enter = span.enter();
+            tracing::info!(progress.inc = 1, item = 5, "processed");
+        });
+
+        let target = module_path!();
+
+        assert_eq!(
+            lines,
+            [
+                format!(" WARN {}: no progress bar", target),
+                "process 1/2 50.0%".to_string(),
+                format!(" INFO {}: processed item=5", target),
+                "process 1/2 100.0%".to_string(),
+            ]
+        );
+    }
+}
@@ -1,3 +1,12 @@
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+#[cfg(test)]
+use crate::progress::OutputStream;
+
 static ITER_UNITS: &[&str] = &["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei", "Zi", "Yi"];
 
 pub fn convert_to_unit(mut count: f32) -> (f32, &'static str) {
@@ -10,3 +19,106 @@ pub fn convert_to_unit(mut count: f32) -> (f32, &'static str) {
 
     (count, ITER_UNITS[suffix_index])
 }
+
+/// Get the expected number of remaining items from a size hint, if it is
+/// known. A lower bound of 0 only means that there may be no item left, as
+/// for filtered iterators, unless the upper bound is also 0.
+pub fn expected_len(size_hint: (usize, Option<usize>)) -> Option<usize> {
+    match size_hint {
+        (0, Some(0)) => Some(0),
+        (0, _) => None,
+        (lower, _) => Some(lower),
+    }
+}
+
+/// Compute the number of columns a text takes in a terminal.
+pub fn text_width(text: &str) -> usize {
+    text.graphemes(true).map(UnicodeWidthStr::width).sum()
+}
+
+/// Cut a text so that it fits in `width` columns, without splitting a
+/// grapheme. An ellipsis is added at the end of the text if it is cut.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text_width(text) <= width {
+        return text.to_string();
+    }
+
+    let mut result = String::new();
+    let mut result_width = 0;
+
+    if width == 0 {
+        return result;
+    }
+
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = grapheme.width();
+
+        if result_width + grapheme_width + 1 > width {
+            break;
+        }
+
+        result.push_str(grapheme);
+        result_width += grapheme_width;
+    }
+
+    result.push('…');
+    result
+}
+
+/// Build an output stream writing into a buffer, and a function returning
+/// what was written so far.
+#[cfg(test)]
+pub fn buffer_output() -> (OutputStream, impl Fn() -> String + Send + 'static) {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let output_stream = OutputStream::shared(buffer.clone());
+    let output = move || String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+    (output_stream, output)
+}
+
+/// Build an output stream discarding everything written into it.
+#[cfg(test)]
+pub fn sink_output() -> OutputStream {
+    OutputStream::writer(std::io::sink())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_len_of_size_hints() {
+        assert_eq!(expected_len((0..100).size_hint()), Some(100));
+        assert_eq!(expected_len((0..0).size_hint()), Some(0));
+        assert_eq!(
+            expected_len((0..100).filter(|i| i % 2 == 0).size_hint()),
+            None
+        );
+        assert_eq!(expected_len((0, None)), None);
+        assert_eq!(expected_len((10, None)), Some(10));
+        assert_eq!(expected_len((10, Some(20))), Some(10));
+    }
+
+    #[test]
+    fn width_of_multibyte_text() {
+        assert_eq!(text_width("héllo"), 5);
+        assert_eq!(text_width("e\u{301}te\u{301}"), 3);
+        assert_eq!(text_width("日本語"), 6);
+        assert_eq!(text_width("🦀 crab"), 7);
+    }
+
+    #[test]
+    fn truncate_multibyte_text() {
+        assert_eq!(truncate("héllo wörld", 6), "héllo…");
+        assert_eq!(truncate("e\u{301}te\u{301}", 2), "e\u{301}…");
+        assert_eq!(truncate("日本語", 6), "日本語");
+        assert_eq!(truncate("日本語", 4), "日…");
+        assert_eq!(truncate("🦀🦀🦀", 5), "🦀🦀…");
+        assert_eq!(truncate("🦀🦀🦀", 0), "");
+
+        for text in ["héllo wörld", "e\u{301}te\u{301}", "日本語", "🦀🦀🦀"] {
+            for width in 0..=text_width(text) {
+                assert!(text_width(&truncate(text, width)) <= width);
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Defines a wrapper around writers to display a progress bar.
+
+use crate::step_progress::{StepProgress, WithStepProgress};
+
+use std::io;
+
+/// A wrapper write only stream arround any writer, which counts written
+/// bytes.
+///
+/// The total size is unknown by default, it can be specified using
+/// `with_max_step`. The progress bar is finished by `finish`, or when the
+/// wrapper is dropped.
+///
+/// # Example
+///
+/// ```
+/// use prog_rs::prelude::*;
+/// use prog_rs::WriteProgress;
+/// # use std::io::Write;
+///
+/// let mut writer = WriteProgress::new(Vec::new())
+///     .with_max_step(1_000_000)
+///     .with_prefix("Export ...");
+///
+/// for _ in 0..1_000 {
+///     writer.write_all(&[0; 1_000]).unwrap();
+/// }
+///
+/// let data = writer.into_inner().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct WriteProgress<W>
+where
+    W: io::Write,
+{
+    /// Only taken out by `into_inner`.
+    inner: Option<W>,
+    step_progress: StepProgress,
+}
+
+impl<W> WriteProgress<W>
+where
+    W: io::Write,
+{
+    /// Wrap a writer.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner: Some(inner),
+            step_progress: StepProgress::new().with_humanize(true).with_unit("B"),
+        }
+    }
+
+    /// Get a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().expect("writer was taken")
+    }
+
+    /// Flush the writer and end the progress bar.
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.inner_mut().flush()?;
+        self.step_progress.finish();
+        Ok(())
+    }
+
+    /// Flush the writer, end the progress bar and unwrap the writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.finish()?;
+        Ok(self.inner.take().expect("writer was taken"))
+    }
+
+    fn inner_mut(&mut self) -> &mut W {
+        self.inner.as_mut().expect("writer was taken")
+    }
+}
+
+impl<W> io::Write for WriteProgress<W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let res = self.inner_mut().write(buf);
+
+        if let Ok(step) = res {
+            self.step_progress.step(step);
+        }
+
+        res
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let res = self.inner_mut().write_vectored(bufs);
+
+        if let Ok(step) = res {
+            self.step_progress.step(step);
+        }
+
+        res
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner_mut().flush()
+    }
+}
+
+impl<W> Drop for WriteProgress<W>
+where
+    W: io::Write,
+{
+    fn drop(&mut self) {
+        if let Some(inner) = &mut self.inner {
+            inner.flush().ok();
+            self.step_progress.finish();
+        }
+    }
+}
+
+impl<W> WithStepProgress for WriteProgress<W>
+where
+    W: io::Write,
+{
+    fn get_step_progress(&mut self) -> &mut StepProgress {
+        &mut self.step_progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::prelude::*;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::progress::{DisplayMode, WithProgress};
+    use crate::utils::buffer_output;
+
+    /// Write 100 bytes in two steps with `f`, and get printed lines.
+    fn write_with<F>(f: F) -> Vec<String>
+    where
+        F: FnOnce(WriteProgress<Vec<u8>>),
+    {
+        let (output_stream, output) = buffer_output();
+
+        let mut writer = WriteProgress::new(Vec::new())
+            .with_max_step(100)
+            .with_humanize(false)
+            .with_unit("")
+            .with_template("{pos}/{total}".parse().unwrap())
+            .with_display_mode(DisplayMode::Log)
+            .with_refresh_delay(Duration::from_secs(3600))
+            .with_output_stream(output_stream);
+
+        writer.write_all(&[0; 50]).unwrap();
+        writer.write_all(&[1; 50]).unwrap();
+        f(writer);
+        output().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn finish_when_dropped() {
+        assert_eq!(write_with(drop), ["50/100", "100/100"]);
+    }
+
+    #[test]
+    fn finish_once_with_into_inner() {
+        let lines = write_with(|writer| {
+            let data = writer.into_inner().unwrap();
+            assert_eq!(data.len(), 100);
+            assert_eq!(data[50..], [1; 50]);
+        });
+
+        assert_eq!(lines, ["50/100", "100/100"]);
+    }
+}